@@ -1,30 +1,147 @@
+use leptos::ev;
 use leptos::prelude::*;
+use std::time::Duration;
+
+use crate::animation::{AnimationSpeed, CELL_MS, DRUMROLL_MS, REVEAL_MS};
+use crate::engine::{FillOrder, GameState};
+use crate::keyboard::typing_in_field;
+
+/// 金額下方按鈕的動作
+#[derive(Clone, Copy, PartialEq)]
+enum AmountAction {
+    None,
+    /// 再玩一次
+    Reset,
+    /// 確認開獎
+    Confirm,
+}
 
 #[component]
 pub fn AmountDisplay(
-    selected_numbers: ReadSignal<Vec<u8>>,
-    digits_needed: ReadSignal<u8>,
-    hidden_mode: ReadSignal<bool>,
-    game_finished: ReadSignal<bool>,
-    set_game_finished: WriteSignal<bool>,
+    game: ReadSignal<GameState>,
+    set_game: WriteSignal<GameState>,
+    /// 本局隱藏的位數
+    hidden_slots: ReadSignal<Vec<usize>>,
+    /// 目前仍以 `$` 顯示的位數
+    concealed: Signal<Vec<usize>>,
+    set_revealed: WriteSignal<Vec<usize>>,
+    /// 多人場次時顯示輪到誰
+    current_player: Signal<Option<String>>,
+    /// 動畫速度；關閉時隱藏的位數一次揭曉
+    speed: Signal<AnimationSpeed>,
     on_reset: Callback<()>,
 ) -> impl IntoView {
+    let reveal_timer = StoredValue::new(None::<IntervalHandle>);
+    let stop_reveal = move || {
+        if let Some(handle) = reveal_timer.try_update_value(Option::take).flatten() {
+            handle.clear();
+        }
+    };
+    on_cleanup(stop_reveal);
+
+    // 確認開獎：鎖定結果後，由低位往高位逐一揭曉隱藏的位數
+    let on_confirm = move || {
+        set_game.update(|g| {
+            g.finish();
+        });
+        stop_reveal();
+        let delay = speed.get_untracked().scale(REVEAL_MS);
+        if delay == 0 {
+            let rest = concealed.get_untracked();
+            set_revealed.update(|r| r.extend(rest));
+            return;
+        }
+        let reveal_next = move || {
+            let next = concealed.with_untracked(|c| c.first().copied());
+            match next {
+                Some(pos) if game.with_untracked(GameState::is_finished) => {
+                    set_revealed.update(|r| r.push(pos));
+                }
+                _ => stop_reveal(),
+            }
+        };
+        if let Ok(handle) =
+            set_interval_with_handle(reveal_next, Duration::from_millis(u64::from(delay)))
+        {
+            reveal_timer.set_value(Some(handle));
+        }
+    };
+
+    // 金額下方的按鈕：分段揭曉中不顯示
+    let action = Memo::new(move |_| {
+        let hidden = hidden_slots.with(|h| !h.is_empty());
+        let state = game.get();
+        let finished = state.is_finished();
+        let all_selected = state.is_complete();
+        let revealing = finished && concealed.with(|c| !c.is_empty());
+        // 按到「爆」直接結束，隱藏的位數不用揭曉
+        if state.is_busted() {
+            AmountAction::Reset
+        } else if revealing {
+            AmountAction::None
+        } else if finished || (!hidden && all_selected) {
+            AmountAction::Reset
+        } else if hidden && all_selected {
+            AmountAction::Confirm
+        } else {
+            AmountAction::None
+        }
+    });
+
+    // 空白鍵按下目前顯示的按鈕
+    let keydown = window_event_listener(ev::keydown, move |ev| {
+        if ev.key() != " " || ev.repeat() || typing_in_field(&ev) {
+            return;
+        }
+        match action.get_untracked() {
+            AmountAction::Reset => on_reset.run(()),
+            AmountAction::Confirm => on_confirm(),
+            AmountAction::None => return,
+        }
+        ev.prevent_default();
+    });
+    on_cleanup(move || keydown.remove());
+
+    // 揭曉前抖動與位數狀態變化的時間
+    let animation_vars = move || {
+        let s = speed.get();
+        format!(
+            "--drumroll-duration: {}ms; --cell-transition: {}ms",
+            s.scale(DRUMROLL_MS),
+            s.scale(CELL_MS)
+        )
+    };
+
     view! {
-        <div class="amount-display">
+        <div class="amount-display" style=animation_vars>
+            {move || {
+                current_player
+                    .get()
+                    .map(|text| view! { <div class="amount-player">{text}</div> })
+            }}
             <div class="amount-digits">
                 {move || {
-                    let needed = digits_needed.get() as usize;
-                    let nums = selected_numbers.get();
-                    let hidden = hidden_mode.get();
-                    let finished = game_finished.get();
-                    let last_idx = needed.saturating_sub(1);
-                    (0..needed)
+                    let state = game.get();
+                    let slots = state.slots().to_vec();
+                    let finished = state.is_finished();
+                    let concealed = concealed.get();
+                    // 開獎後下一個要揭曉的位數
+                    let drumroll = concealed
+                        .first()
+                        .copied()
+                        .filter(|_| finished && speed.get() != AnimationSpeed::Off);
+                    let next = state.next_slot();
+                    let latest = state.pick_order().last().copied();
+                    let choosable = (state.rules().fill_order == FillOrder::PlayerChosen
+                        || state.is_free_pick())
+                        && !finished;
+                    slots
+                        .into_iter()
+                        .enumerate()
                         .rev()
-                        .map(|i| {
-                            let digit = nums.get(i).copied();
+                        .map(|(i, digit)| {
                             let filled = digit.is_some();
-                            // 隱藏模式只隱藏最後一位（最高位）
-                            let is_hidden = hidden && !finished && i == last_idx;
+                            let is_hidden = concealed.contains(&i);
                             let text = if is_hidden {
                                 "$".to_string()
                             } else {
@@ -35,7 +152,19 @@ pub fn AmountDisplay(
                                     class="amount-digit"
                                     class:filled=filled
                                     class:hidden=is_hidden
-                                    class:revealed=finished
+                                    class:revealed=finished && !is_hidden
+                                    class:drumroll=drumroll == Some(i)
+                                    class:next=next == Some(i)
+                                    class:latest=latest == Some(i) && !finished
+                                    class:choosable=choosable && !filled
+                                    on:click=move |_| {
+                                        if choosable {
+                                            set_game
+                                                .update(|g| {
+                                                    g.choose_slot(i);
+                                                });
+                                        }
+                                    }
                                 >
                                     {text}
                                 </span>
@@ -45,31 +174,57 @@ pub fn AmountDisplay(
                 }}
             </div>
 
+            // 特殊格的效果：倍數、爆掉的安慰獎、自選提示；開獎後顯示最後的金額
+            {move || {
+                let state = game.get();
+                let settled = state.is_finished()
+                    || (hidden_slots.with(Vec::is_empty) && state.is_complete());
+                let revealed = concealed.with(Vec::is_empty);
+                if state.is_busted() {
+                    let text = format!("爆！安慰獎 {}", state.payout());
+                    return Some(view! { <div class="amount-special bust">{text}</div> }.into_any());
+                }
+                let multiplier = state.multiplier();
+                if multiplier > 1 {
+                    let total = (settled && revealed)
+                        .then(|| format!("開獎金額 {}", state.payout()));
+                    return Some(
+                        view! {
+                            <div class="amount-special">
+                                <span class="amount-multiplier">{format!("×{}", multiplier)}</span>
+                                {total.map(|t| view! { <span class="amount-payout">{t}</span> })}
+                            </div>
+                        }
+                            .into_any(),
+                    );
+                }
+                state
+                    .is_free_pick()
+                    .then(|| {
+                        view! { <div class="amount-special">"自選：點選下一個數字要填入的位數"</div> }
+                            .into_any()
+                    })
+            }}
+
             <div class="amount-actions">
-                {move || {
-                    let hidden = hidden_mode.get();
-                    let finished = game_finished.get();
-                    let nums = selected_numbers.get();
-                    let needed = digits_needed.get();
-                    let all_selected = nums.len() as u8 == needed;
-                    if finished || (!hidden && all_selected) {
+                {move || match action.get() {
+                    AmountAction::Reset => {
                         view! {
                             <button class="reset-btn" on:click=move |_| on_reset.run(())>
                                 "再玩一次"
                             </button>
                         }
                             .into_any()
-                    } else if hidden && all_selected {
+                    }
+                    AmountAction::Confirm => {
                         view! {
-                            <button
-                                class="confirm-btn"
-                                on:click=move |_| set_game_finished.set(true)
-                            >
+                            <button class="confirm-btn" on:click=move |_| on_confirm()>
                                 "確認開獎！"
                             </button>
                         }
                             .into_any()
-                    } else {
+                    }
+                    AmountAction::None => {
                         view! { <span class="amount-actions-placeholder"></span> }.into_any()
                     }
                 }}
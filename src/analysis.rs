@@ -0,0 +1,198 @@
+//! 設定的金額分佈分析：每次都從可選的格子中平均抽一個時，最終金額的分佈。
+//! 位數不多時精確列舉，否則以模擬估計；網頁版與命令列版共用
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::Serialize;
+
+use crate::engine::{
+    amount_distribution, DigitWeights, FillOrder, GameRules, GameState, MAX_ENUM_DIGITS,
+};
+
+/// 無法精確列舉時模擬的局數
+pub const SIMULATION_ROUNDS: usize = 5_000;
+
+/// 顯示的百分位數
+pub const PERCENTILES: [u8; 5] = [10, 25, 50, 75, 90];
+
+/// 機率分佈直方圖的區間數
+const HISTOGRAM_BUCKETS: usize = 10;
+
+/// 金額分佈的統計結果
+#[derive(Clone, PartialEq, Serialize)]
+pub struct Analysis {
+    /// 精確列舉；否則為模擬 [`SIMULATION_ROUNDS`] 局的估計
+    pub exact: bool,
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+    /// (百分位數, 金額)
+    pub percentiles: Vec<(u8, u64)>,
+    /// (區間起點, 區間終點, 機率)
+    pub histogram: Vec<(u64, u64, f64)>,
+}
+
+/// 將機率分佈依金額等分成數個區間，回傳 (區間起點, 區間終點, 機率)；
+/// 寬度無條件進位，只有最後一個區間可能較窄
+pub fn histogram(dist: &[(u64, f64)]) -> Vec<(u64, u64, f64)> {
+    let (Some(&(lo, _)), Some(&(hi, _))) = (dist.first(), dist.last()) else {
+        return Vec::new();
+    };
+    let width = (hi - lo + 1).div_ceil(HISTOGRAM_BUCKETS as u64);
+    let mut buckets: Vec<(u64, u64, f64)> = (0..HISTOGRAM_BUCKETS as u64)
+        .map(|i| (lo + i * width, lo + (i + 1) * width - 1, 0.0))
+        .take_while(|&(start, _, _)| start <= hi)
+        .collect();
+    if let Some(last) = buckets.last_mut() {
+        last.1 = last.1.min(hi);
+    }
+    for &(amount, p) in dist {
+        let idx = (((amount - lo) / width) as usize).min(buckets.len() - 1);
+        buckets[idx].2 += p;
+    }
+    buckets
+}
+
+/// 模擬一局：每次從可選的數字中平均抽一個
+fn simulate_round(rules: &GameRules, rng: &mut impl Rng) -> Option<u64> {
+    let mut state = GameState::new(rules.clone());
+    while !state.is_complete() {
+        let candidates: Vec<u8> = rules
+            .alphabet
+            .digits
+            .iter()
+            .copied()
+            .filter(|&d| state.can_pick(d))
+            .collect();
+        let digit = *candidates.choose(rng)?;
+        state.press(digit, rng).ok()?;
+    }
+    Some(state.amount())
+}
+
+/// 模擬多局，回傳與 [`amount_distribution`] 相同格式的分佈
+fn simulate(rules: &GameRules, rng: &mut impl Rng) -> Vec<(u64, f64)> {
+    let mut amounts: Vec<u64> = (0..SIMULATION_ROUNDS)
+        .filter_map(|_| simulate_round(rules, rng))
+        .collect();
+    amounts.sort_unstable();
+    let share = 1.0 / amounts.len() as f64;
+    let mut dist: Vec<(u64, f64)> = Vec::new();
+    for amount in amounts {
+        match dist.last_mut() {
+            Some((last, p)) if *last == amount => *p += share,
+            _ => dist.push((amount, share)),
+        }
+    }
+    dist
+}
+
+/// 累積機率第一次達到 `q`% 的金額
+fn percentile(dist: &[(u64, f64)], q: u8) -> u64 {
+    let target = q as f64 / 100.0;
+    let mut cumulative = 0.0;
+    for &(amount, p) in dist {
+        cumulative += p;
+        if cumulative >= target - 1e-9 {
+            return amount;
+        }
+    }
+    dist.last().map_or(0, |&(amount, _)| amount)
+}
+
+/// 分析金額分佈；自選位數視為隨機位數。規則無法完成時為 `None`
+pub fn analyze(rules: &GameRules, rng: &mut impl Rng) -> Option<Analysis> {
+    if !rules.is_feasible() {
+        return None;
+    }
+    let mut rules = rules.clone();
+    if rules.fill_order == FillOrder::PlayerChosen {
+        rules.fill_order = FillOrder::Random;
+    }
+    let exact = rules.digits_needed <= MAX_ENUM_DIGITS;
+    let dist = if exact {
+        amount_distribution(&rules, &DigitWeights::default())?
+    } else {
+        simulate(&rules, rng)
+    };
+    let (&(min, _), &(max, _)) = (dist.first()?, dist.last()?);
+    Some(Analysis {
+        exact,
+        min,
+        max,
+        mean: dist.iter().map(|&(a, p)| a as f64 * p).sum(),
+        percentiles: PERCENTILES
+            .iter()
+            .map(|&q| (q, percentile(&dist, q)))
+            .collect(),
+        histogram: histogram(&dist),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::engine::DigitAlphabet;
+
+    #[test]
+    fn single_digit_is_uniform() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let rules = GameRules::by_digits(1, false, DigitAlphabet::default());
+        let analysis = analyze(&rules, &mut rng).unwrap();
+        assert!(analysis.exact);
+        assert_eq!((analysis.min, analysis.max), (1, 9));
+        assert!((analysis.mean - 5.0).abs() < 1e-9);
+        assert_eq!(analysis.percentiles[2], (50, 5));
+        let total: f64 = analysis.histogram.iter().map(|&(_, _, p)| p).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn histogram_buckets_have_equal_width() {
+        // 100-149 共 50 個金額：每個區間 5 個，最後一個區間不會吸收餘數
+        let dist: Vec<(u64, f64)> = (100..150).map(|a| (a, 1.0 / 50.0)).collect();
+        let buckets = histogram(&dist);
+        assert_eq!(buckets.len(), 10);
+        assert!(buckets.iter().all(|&(start, end, _)| end - start == 4));
+        assert!(buckets.iter().all(|&(_, _, p)| (p - 0.1).abs() < 1e-9));
+
+        // 111-149 共 39 個金額：寬度進位為 4，最後一個區間只有 3 個
+        let dist: Vec<(u64, f64)> = (111..150).map(|a| (a, 1.0 / 39.0)).collect();
+        let buckets = histogram(&dist);
+        assert_eq!(buckets.last().map(|&(s, e, _)| (s, e)), Some((147, 149)));
+        assert!(buckets[..9].iter().all(|&(start, end, _)| end - start == 3));
+    }
+
+    #[test]
+    fn infeasible_rules_have_no_analysis() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let rules = GameRules::by_range(10, 10, false, DigitAlphabet::default());
+        assert!(analyze(&rules, &mut rng).is_none());
+    }
+
+    #[test]
+    fn many_digits_are_simulated() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let rules = GameRules::by_digits(MAX_ENUM_DIGITS + 1, false, DigitAlphabet::default());
+        let analysis = analyze(&rules, &mut rng).unwrap();
+        assert!(!analysis.exact);
+        assert!(analysis.min >= 111_111 && analysis.max <= 999_999);
+        let percentiles: Vec<u64> = analysis.percentiles.iter().map(|&(_, a)| a).collect();
+        assert!(percentiles.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn player_chosen_is_analyzed_as_random() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut rules = GameRules::by_max_amount(150, false, DigitAlphabet::default());
+        let random = {
+            rules.fill_order = FillOrder::Random;
+            analyze(&rules, &mut rng).unwrap()
+        };
+        rules.fill_order = FillOrder::PlayerChosen;
+        assert!(analyze(&rules, &mut rng).unwrap() == random);
+    }
+}
@@ -0,0 +1,85 @@
+use leptos::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::analysis::{analyze, SIMULATION_ROUNDS};
+use crate::engine::GameRules;
+
+/// 模擬用的固定種子，相同設定每次顯示相同的估計
+const ANALYSIS_SEED: u64 = 0;
+
+/// 直方圖：每個區間一根長條，高度相對於最高的區間
+#[component]
+pub fn DistributionBars(buckets: Vec<(u64, u64, f64)>) -> impl IntoView {
+    let peak = buckets.iter().map(|b| b.2).fold(0.0, f64::max);
+    view! {
+        <div class="distribution-bars">
+            {buckets
+                .into_iter()
+                .map(|(start, end, p)| {
+                    let height = if peak > 0.0 { p / peak * 100.0 } else { 0.0 };
+                    view! {
+                        <div
+                            class="distribution-bar"
+                            title=format!("{}-{}: {:.1}%", start, end, p * 100.0)
+                        >
+                            <div
+                                class="distribution-bar-fill"
+                                style=format!("height: {:.1}%", height)
+                            ></div>
+                            <span class="distribution-bar-label">{start.to_string()}</span>
+                        </div>
+                    }
+                })
+                .collect::<Vec<_>>()}
+        </div>
+    }
+}
+
+#[component]
+pub fn AnalysisPanel(rules: Memo<GameRules>) -> impl IntoView {
+    let analysis = Memo::new(move |_| {
+        rules.with(|r| analyze(r, &mut ChaCha8Rng::seed_from_u64(ANALYSIS_SEED)))
+    });
+
+    view! {
+        <div class="analysis-panel">
+            <h2>"預估分佈"</h2>
+            {move || {
+                analysis
+                    .get()
+                    .map(|a| {
+                        let method = if a.exact {
+                            "精確計算".to_string()
+                        } else {
+                            format!("模擬 {} 局估計", SIMULATION_ROUNDS)
+                        };
+                        view! {
+                            <p class="analysis-hint">
+                                {format!("每次從可選的格子中平均抽一個時的金額分佈（{}）", method)}
+                            </p>
+                            <div class="analysis-stats">
+                                <span>{format!("最小 {}", a.min)}</span>
+                                <span>{format!("平均 {:.1}", a.mean)}</span>
+                                <span>{format!("最大 {}", a.max)}</span>
+                            </div>
+                            <div class="analysis-percentiles">
+                                {a
+                                    .percentiles
+                                    .iter()
+                                    .map(|&(q, amount)| {
+                                        view! {
+                                            <span class="analysis-percentile">
+                                                {format!("P{} {}", q, amount)}
+                                            </span>
+                                        }
+                                    })
+                                    .collect::<Vec<_>>()}
+                            </div>
+                            <DistributionBars buckets=a.histogram />
+                        }
+                    })
+            }}
+        </div>
+    }
+}
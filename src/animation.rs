@@ -0,0 +1,195 @@
+//! 九宮格動畫的排程。排列與 transform 在操作當下就更新，動畫只負責呈現：
+//! 播放中畫面顯示動畫開始時的盤面，等 `animationend`／`transitionend` 到達才換下一段。
+//! 所有動畫時間都由 [`AnimationSpeed`] 換算
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use leptos::prelude::*;
+
+/// 一般速度下九宮格轉動（transform transition）的時間
+pub const TURN_MS: u32 = 600;
+/// 一般速度下轉動一排的時間
+pub const SLIDE_MS: u32 = 400;
+/// 一般速度下翻牌與自動抽中閃爍的時間
+pub const FLIP_MS: u32 = 500;
+/// 一般速度下格子選取、hover 等狀態變化的時間
+pub const CELL_MS: u32 = 300;
+/// 一般速度下分段開獎每揭曉一位的間隔
+pub const REVEAL_MS: u32 = 1200;
+/// 一般速度下下一個要揭曉的位數抖動一次的時間
+pub const DRUMROLL_MS: u32 = 150;
+/// 結束事件沒有到達時，多等這麼久就直接換下一段
+const FALLBACK_MARGIN_MS: u32 = 250;
+
+/// 動畫速度
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AnimationSpeed {
+    /// 不播放動畫，盤面直接換成結果
+    Off,
+    Fast,
+    #[default]
+    Normal,
+    Dramatic,
+}
+
+impl AnimationSpeed {
+    pub const ALL: [AnimationSpeed; 4] = [
+        AnimationSpeed::Off,
+        AnimationSpeed::Fast,
+        AnimationSpeed::Normal,
+        AnimationSpeed::Dramatic,
+    ];
+
+    /// 由 URL 參數解析
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|speed| speed.to_query() == s)
+    }
+
+    pub fn to_query(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Fast => "fast",
+            Self::Normal => "normal",
+            Self::Dramatic => "dramatic",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => "關閉",
+            Self::Fast => "快速",
+            Self::Normal => "一般",
+            Self::Dramatic => "慢動作",
+        }
+    }
+
+    /// 以一般速度的時間 `base` 換算成這個速度的時間（毫秒）
+    pub fn scale(self, base: u32) -> u32 {
+        match self {
+            Self::Off => 0,
+            Self::Fast => base / 2,
+            Self::Normal => base,
+            Self::Dramatic => base * 2,
+        }
+    }
+}
+
+/// 系統是否設定減少動態效果（`prefers-reduced-motion`）
+pub fn prefers_reduced_motion() -> bool {
+    web_sys::window()
+        .and_then(|w| {
+            w.match_media("(prefers-reduced-motion: reduce)")
+                .ok()
+                .flatten()
+        })
+        .is_some_and(|m| m.matches())
+}
+
+/// 一段動畫期間畫面上顯示的盤面
+#[derive(Clone, PartialEq)]
+pub struct Frame {
+    pub grid: Vec<u8>,
+    pub transform: usize,
+    /// 套用在九宮格上的 CSS animation class；轉動 transform 時為 `None`（CSS transition）
+    pub class: Option<&'static str>,
+    /// 排列變換時每一格移動的 (欄數, 列數)；其他動畫為空
+    pub offsets: Vec<(i32, i32)>,
+}
+
+impl Frame {
+    /// 一般速度下這段動畫的時間
+    fn base_ms(&self) -> u32 {
+        match self.class {
+            None => TURN_MS,
+            Some(_) => SLIDE_MS,
+        }
+    }
+}
+
+/// 動畫控制器：一次播放一段，其餘依序排隊。
+/// 結束事件沒有到達時（例如 transform 沒有改變，不會觸發 `transitionend`），
+/// 超過動畫時間後自動換下一段，避免一直不能選取
+#[derive(Clone, Copy)]
+pub struct Animator {
+    speed: Signal<AnimationSpeed>,
+    playing: RwSignal<Option<Frame>>,
+    queue: StoredValue<VecDeque<Frame>>,
+    /// 每開始播放一段加一，逾時時確認還是同一段
+    serial: StoredValue<u64>,
+}
+
+impl Animator {
+    pub fn new(speed: Signal<AnimationSpeed>) -> Self {
+        Self {
+            speed,
+            playing: RwSignal::new(None),
+            queue: StoredValue::new(VecDeque::new()),
+            serial: StoredValue::new(0),
+        }
+    }
+
+    /// 開始播放 `frame`（`None` 為停止），並在逾時後換下一段
+    fn start(&self, frame: Option<Frame>) {
+        let serial = self.serial.try_update_value(|s| {
+            *s += 1;
+            *s
+        });
+        let timeout = frame
+            .as_ref()
+            .map(|f| self.speed.get_untracked().scale(f.base_ms()) + FALLBACK_MARGIN_MS);
+        self.playing.set(frame);
+        let (Some(serial), Some(ms)) = (serial, timeout) else {
+            return;
+        };
+        let animator = *self;
+        set_timeout(
+            move || {
+                if animator.serial.try_get_value() == Some(serial) {
+                    animator.advance();
+                }
+            },
+            Duration::from_millis(ms as u64),
+        );
+    }
+
+    /// 播放一段動畫；已有動畫進行中時排在後面，關閉動畫時不播放
+    pub fn play(&self, frame: Frame) {
+        if self.speed.get_untracked() == AnimationSpeed::Off {
+            return;
+        }
+        if self.playing.with_untracked(Option::is_some) {
+            self.queue.update_value(|q| q.push_back(frame));
+        } else {
+            self.start(Some(frame));
+        }
+    }
+
+    /// 目前這段動畫結束，換下一段
+    pub fn advance(&self) {
+        let next = self.queue.try_update_value(VecDeque::pop_front).flatten();
+        self.start(next);
+    }
+
+    /// 取消進行中與排隊的動畫，畫面直接顯示目前的盤面
+    pub fn cancel(&self) {
+        self.queue.update_value(VecDeque::clear);
+        if self.playing.with_untracked(Option::is_some) {
+            self.start(None);
+        }
+    }
+
+    /// 有動畫進行中（含排隊），此時不能選取
+    pub fn is_busy(&self) -> bool {
+        self.playing.with(Option::is_some)
+    }
+
+    pub fn is_busy_untracked(&self) -> bool {
+        self.playing.with_untracked(Option::is_some)
+    }
+
+    /// 目前正在播放的盤面
+    pub fn frame(&self) -> Option<Frame> {
+        self.playing.get()
+    }
+}
@@ -1,151 +1,605 @@
+use std::collections::BTreeMap;
+
+use leptos::ev;
 use leptos::prelude::*;
-use web_sys::UrlSearchParams;
+use rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
 use crate::amount_display::AmountDisplay;
+use crate::analysis_panel::AnalysisPanel;
+use crate::animation::{prefers_reduced_motion, AnimationSpeed, Animator};
+use crate::budget::{Budget, BudgetPanel};
+use crate::config::{is_layout_of, read_config_from_url, sync_url_query, GameConfig};
+use crate::digit_weights::DigitWeightsPanel;
+use crate::engine::{Event, GameRules, GameState, HiddenPolicy, SpecialTiles};
+use crate::fair::{actions_to_string, FairOptions, FairRound, PadAction};
+use crate::fair_panel::FairPanel;
 use crate::game_options::GameOptions;
-use crate::number_pad::NumberPad;
+use crate::hidden_options::HiddenOptions;
+use crate::history::{load_history, now_iso, save_history, HistoryPanel, RoundRecord};
+use crate::keyboard::typing_in_field;
+use crate::number_pad::{NumberPad, PadPress};
+use crate::pad::PadLayout;
 use crate::range_config::{LimitMode, RangeConfig};
+use crate::red_envelope::{RedEnvelope, RedEnvelopePanel};
+use crate::session::{Session, SessionPanel};
+use crate::undo::{Snapshot, UndoButtons, UndoHistory};
 
-const DEFAULT_DIGITS: u8 = 4;
-const DEFAULT_MAX_AMOUNT: u64 = 999;
-
-struct InitConfig {
-    mode: LimitMode,
-    digits: u8,
-    max_amount: u64,
-}
+#[component]
+pub fn App() -> impl IntoView {
+    let (config, errors) = read_config_from_url();
+    // 網址中無效的參數名稱
+    let (config_errors, set_config_errors) = signal(errors);
 
-impl Default for InitConfig {
-    fn default() -> Self {
-        Self {
-            mode: LimitMode::ByDigits,
-            digits: DEFAULT_DIGITS,
-            max_amount: DEFAULT_MAX_AMOUNT,
+    let (mode, set_mode) = signal(config.mode);
+    let (selected_digits, set_selected_digits) = signal(config.digits);
+    let (max_amount, set_max_amount) = signal(config.max_amount);
+    let (min_amount, set_min_amount) = signal(config.min_amount);
+    let (no_repeat, set_no_repeat) = signal(config.no_repeat);
+    let (hidden_policy, set_hidden_policy) = signal(config.hidden);
+    let (no_undo, set_no_undo) = signal(config.no_undo);
+    let (fair, set_fair) = signal(config.fair);
+    let (fill_order, set_fill_order) = signal(config.fill_order);
+    let (face_down, set_face_down) = signal(config.face_down);
+    let (alphabet, set_alphabet) = signal(config.alphabet);
+    let (spin_style, set_spin_style) = signal(config.spin_style);
+    let (specials, set_specials) = signal(config.specials);
+    let (consolation, set_consolation) = signal(config.consolation);
+    // 九宮格的初始排列：可用數字接著啟用的特殊格
+    let pad_cells = Memo::new(move |_| specials.with(|s| alphabet.with(|a| s.pad_cells(a))));
+    let (speed, set_speed) = signal(config.speed);
+    // 實際的動畫速度：系統設定減少動態效果時一律關閉
+    let reduced_motion = prefers_reduced_motion();
+    let motion = Signal::derive(move || {
+        if reduced_motion {
+            AnimationSpeed::Off
+        } else {
+            speed.get()
         }
-    }
-}
+    });
+    // 九宮格的動畫；復原／重做直接換盤面時結束
+    let animator = Animator::new(motion);
+    // 未指定種子時使用啟動時隨機產生的種子
+    let (seed, set_seed) = signal(config.seed);
+    let fallback_seed: u64 = thread_rng().gen();
+    let seed_used = Memo::new(move |_| seed.get().unwrap_or(fallback_seed));
+    // 公正驗證：每局產生新的種子，開獎前只公開承諾值
+    let (fair_round, set_fair_round) = signal(FairRound::generate(&mut thread_rng()));
+    let pad_seed = Memo::new(move |_| {
+        if fair.get() {
+            fair_round.with(|r| r.seed)
+        } else {
+            seed_used.get()
+        }
+    });
+    let (weights, set_weights) = signal(config.weights);
+    let (grid_numbers, set_grid_numbers) = signal(config.grid);
+    let (grid_transform, set_grid_transform) = signal(config.transform);
+    let (pad_size, set_pad_size) = signal(config.pad_size);
+    let (layout, set_layout) = signal(config.layout);
+    // 九宮格的列數與欄數：放不下目前的可用數字與特殊格時改用預設排法
+    let pad_layout = Memo::new(move |_| {
+        let len = pad_cells.with(Vec::len);
+        layout
+            .get()
+            .filter(|l| l.fits(len))
+            .unwrap_or_else(|| PadLayout::auto(len))
+    });
 
-fn read_config_from_url() -> InitConfig {
-    let params = web_sys::window()
-        .and_then(|w| w.location().search().ok())
-        .and_then(|s| UrlSearchParams::new_with_str(&s).ok());
+    // 數字組或特殊格變更後，九宮格不再是其排列時恢復原始順序
+    Effect::new(move |_| {
+        pad_cells.with(|cells| {
+            if !grid_numbers.with_untracked(|g| is_layout_of(g, cells)) {
+                set_grid_numbers.set(cells.clone());
+            }
+        });
+    });
+
+    // 變更種子時九宮格回到初始狀態，相同種子與操作順序才能重現盤面
+    let on_seed_change = Callback::new(move |s: Option<u64>| {
+        set_seed.set(s);
+        set_grid_numbers.set(pad_cells.get_untracked());
+        set_grid_transform.set(0);
+    });
+
+    // 所有設定同步到網址
+    let last_synced = StoredValue::new(None::<GameConfig>);
+    Effect::new(move |_| {
+        let current = GameConfig {
+            mode: mode.get(),
+            digits: selected_digits.get(),
+            max_amount: max_amount.get(),
+            min_amount: min_amount.get(),
+            no_repeat: no_repeat.get(),
+            hidden: hidden_policy.get(),
+            no_undo: no_undo.get(),
+            fair: fair.get(),
+            fill_order: fill_order.get(),
+            face_down: face_down.get(),
+            spin_style: spin_style.get(),
+            specials: specials.get(),
+            consolation: consolation.get(),
+            speed: speed.get(),
+            alphabet: alphabet.get(),
+            seed: seed.get(),
+            weights: weights.get(),
+            pad_size: pad_size.get(),
+            grid: grid_numbers.get(),
+            transform: grid_transform.get(),
+            layout: layout.get(),
+        };
+        last_synced.with_value(|prev| sync_url_query(&current, prev.as_ref()));
+        last_synced.set_value(Some(current));
+    });
 
-    let Some(p) = params else {
-        return InitConfig::default();
+    // 上一頁／下一頁：從網址還原設定
+    let popstate = window_event_listener(ev::popstate, move |_| {
+        let (config, errors) = read_config_from_url();
+        set_mode.set(config.mode);
+        set_selected_digits.set(config.digits);
+        set_max_amount.set(config.max_amount);
+        set_min_amount.set(config.min_amount);
+        set_no_repeat.set(config.no_repeat);
+        set_hidden_policy.set(config.hidden);
+        set_no_undo.set(config.no_undo);
+        set_fair.set(config.fair);
+        set_fill_order.set(config.fill_order);
+        set_face_down.set(config.face_down);
+        set_spin_style.set(config.spin_style);
+        set_specials.set(config.specials);
+        set_consolation.set(config.consolation);
+        set_speed.set(config.speed);
+        set_alphabet.set(config.alphabet);
+        set_seed.set(config.seed);
+        set_weights.set(config.weights);
+        set_grid_numbers.set(config.grid);
+        set_grid_transform.set(config.transform);
+        set_pad_size.set(config.pad_size);
+        set_layout.set(config.layout);
+        set_config_errors.set(errors);
+    });
+    on_cleanup(move || popstate.remove());
+
+    // 獎金預算：選擇最大金額時，本局的最大金額不超過剩餘預算
+    let (budget, set_budget) = signal(None::<Budget>);
+    let budget_locked =
+        Signal::derive(move || budget.with(|b| b.as_ref().is_some_and(Budget::is_exhausted)));
+    let max_cap = Signal::derive(move || {
+        let available = budget.with(|b| b.as_ref().map(Budget::available))?;
+        (mode.get() == LimitMode::ByMaxAmount).then(|| max_amount.get().min(available))
+    });
+
+    // 紅包分配：每一份的範圍由剩餘金額與人數決定，取代範圍設定
+    let (envelope, set_envelope) = signal(None::<RedEnvelope>);
+    let pad_locked = Signal::derive(move || {
+        budget_locked.get() || envelope.with(|e| e.as_ref().is_some_and(RedEnvelope::is_over))
+    });
+
+    let rules = Memo::new(move |_| {
+        let no_rep = no_repeat.get();
+        let al = alphabet.get();
+        let split = envelope.with(|e| e.as_ref().and_then(RedEnvelope::bounds));
+        let mut rules = match (split, mode.get()) {
+            (Some((lo, hi)), _) => GameRules::by_range(lo, hi, no_rep, al),
+            (None, LimitMode::ByDigits) => GameRules::by_digits(selected_digits.get(), no_rep, al),
+            (None, LimitMode::ByMaxAmount) => {
+                let max = max_cap.get().unwrap_or_else(|| max_amount.get());
+                GameRules::by_max_amount(max, no_rep, al)
+            }
+            (None, LimitMode::ByRange) => {
+                GameRules::by_range(min_amount.get(), max_amount.get(), no_rep, al)
+            }
+        };
+        rules.fill_order = fill_order.get();
+        rules.consolation = consolation.get();
+        rules.no_takebacks = no_undo.get() || fair.get();
+        rules
+    });
+    let infeasible = Signal::derive(move || rules.with(|r| !r.is_feasible()));
+    let (game, set_game) = signal(GameState::new(rules.get_untracked()));
+
+    // 本局的復原／重做紀錄；公正驗證時重播必須與實際操作一致，不能復原
+    let (undo_history, set_undo_history) = signal(UndoHistory::default());
+    let undo_enabled = Signal::derive(move || !no_undo.get() && !fair.get());
+
+    // 本局隱藏的位數，見下方決定隱藏位數的 Effect
+    let (hidden_slots, set_hidden_slots) = signal(Vec::<usize>::new());
+    // 公正驗證重播需要的選項
+    let fair_options = Memo::new(move |_| FairOptions {
+        weights: weights.get(),
+        blind: hidden_slots.with(|h| !h.is_empty()) || face_down.get(),
+        face_down: face_down.get(),
+        specials: specials.get(),
+    });
+
+    // 公正驗證的新一局：九宮格回到初始排列，換新的種子與承諾值，並記錄開局時的選項。
+    // 重播從預設排法開始，目前不是預設排法時先記錄
+    let start_fair_round = move || {
+        let mut round = FairRound::generate(&mut thread_rng());
+        round.options = fair_options.get_untracked();
+        let current = pad_layout.get_untracked();
+        if current != PadLayout::auto(pad_cells.with_untracked(Vec::len)) {
+            round.actions.push(PadAction::Layout(current));
+        }
+        set_fair_round.set(round);
+        set_grid_numbers.set(pad_cells.get_untracked());
+        set_grid_transform.set(0);
     };
 
-    if let Some(d) = p.get("digits").and_then(|v| v.parse::<u8>().ok()) {
-        InitConfig {
-            mode: LimitMode::ByDigits,
-            digits: d.clamp(1, 9),
-            ..Default::default()
+    // 模式或參數變更時清空選取
+    Effect::new(move |_| {
+        set_game.set(GameState::new(rules.get()));
+        set_undo_history.update(UndoHistory::clear);
+        if fair.get() {
+            start_fair_round();
         }
-    } else if let Some(m) = p.get("max").and_then(|v| v.parse::<u64>().ok()) {
-        InitConfig {
-            mode: LimitMode::ByMaxAmount,
-            max_amount: m.max(1),
-            ..Default::default()
+    });
+
+    // 公正驗證時，開局後變更權重、翻牌、隱藏或特殊格會讓實際的盤面與驗證資料不符，重新開一局
+    Effect::new(move |_| {
+        let options = fair_options.get();
+        if fair.get_untracked() && fair_round.with_untracked(|r| r.options != options) {
+            set_game.set(GameState::new(rules.get_untracked()));
+            start_fair_round();
         }
-    } else {
-        InitConfig::default()
-    }
-}
+    });
 
-fn sync_url_query(mode: LimitMode, digits: u8, max_amount: u64) {
-    let Some(window) = web_sys::window() else {
-        return;
-    };
-    let Ok(pathname) = window.location().pathname() else {
-        return;
-    };
-    let query = match mode {
-        LimitMode::ByDigits => format!("?digits={}", digits),
-        LimitMode::ByMaxAmount => format!("?max={}", max_amount),
-    };
-    let url = format!("{}{}", pathname, query);
-    let _ = window
-        .history()
-        .and_then(|h| h.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url)));
-}
+    // 本局隱藏的位數：新的一局或隱藏模式變更時重新決定。
+    // 隨機隱藏也由種子決定，使用另一條亂數串流，不影響九宮格的盤面與公正驗證的重播
+    let round_empty = Memo::new(move |_| game.with(GameState::is_empty));
+    let hidden_rng = StoredValue::new(ChaCha8Rng::seed_from_u64(0));
+    Effect::new(move |prev: Option<(HiddenPolicy, u64)>| {
+        let policy = hidden_policy.get();
+        let seed = pad_seed.get();
+        let digits_needed = rules.with(|r| r.digits_needed);
+        let reseeded = prev.as_ref().is_none_or(|&(_, s)| s != seed);
+        if reseeded {
+            hidden_rng.set_value(HiddenPolicy::rng(seed));
+        }
+        if round_empty.get() || prev.is_none_or(|(p, _)| p != policy) {
+            let slots = hidden_rng
+                .try_update_value(|r| policy.hidden_slots(digits_needed, r))
+                .unwrap_or_default();
+            set_hidden_slots.set(slots);
+        }
+        (policy, seed)
+    });
+    // 本局已定案：確認開獎後，或沒有隱藏位數時填滿（隨即開獎並寫入紀錄）
+    let settled = Memo::new(move |_| {
+        let hidden = hidden_slots.with(|h| !h.is_empty());
+        game.with(|g| g.is_finished() || (!hidden && g.is_complete()))
+    });
 
-#[component]
-pub fn App() -> impl IntoView {
-    let config = read_config_from_url();
+    // 沒有隱藏位數時填滿即開獎，之後的選取或取消都不能再改變已記錄的結果；
+    // 公正驗證時並公開種子
+    Effect::new(move |_| {
+        if settled.get() && !game.with(GameState::is_finished) {
+            set_game.update(|g| {
+                g.finish();
+            });
+        }
+    });
 
-    let (mode, set_mode) = signal(config.mode);
-    let (selected_digits, set_selected_digits) = signal(config.digits);
-    let (max_amount, set_max_amount) = signal(config.max_amount);
-    let (no_repeat, set_no_repeat) = signal(false);
-    let (hidden_mode, set_hidden_mode) = signal(false);
-    let (selected_numbers, set_selected_numbers) = signal(Vec::<u8>::new());
-    let (game_finished, set_game_finished) = signal(false);
-
-    let digits_needed = Memo::new(move |_| match mode.get() {
-        LimitMode::ByDigits => selected_digits.get(),
-        LimitMode::ByMaxAmount => {
-            let amt = max_amount.get();
-            if amt == 0 {
-                1
-            } else {
-                (amt as f64).log10().floor() as u8 + 1
-            }
+    // 開獎後不能再復原；關閉悔棋時清掉既有紀錄
+    Effect::new(move |_| {
+        if settled.get() || !undo_enabled.get() {
+            set_undo_history.update(UndoHistory::clear);
         }
     });
 
-    // 模式或參數變更時清空選取並同步 URL
+    // 開獎後已揭曉的隱藏位數；未開獎時清空
+    let (revealed, set_revealed) = signal(Vec::<usize>::new());
     Effect::new(move |_| {
-        let m = mode.get();
-        let d = selected_digits.get();
-        let a = max_amount.get();
-        let _ = digits_needed.get();
-        set_selected_numbers.set(Vec::new());
-        set_game_finished.set(false);
-        sync_url_query(m, d, a);
+        if !game.with(GameState::is_finished) && revealed.with_untracked(|r| !r.is_empty()) {
+            set_revealed.set(Vec::new());
+        }
+    });
+    let concealed = Signal::derive(move || {
+        revealed
+            .with(|r| hidden_slots.with(|h| h.iter().copied().filter(|p| !r.contains(p)).collect()))
+    });
+    // 九宮格遮住數字：有隱藏位數時，直到全部揭曉
+    let masked = Signal::derive(move || {
+        hidden_slots.with(|h| !h.is_empty())
+            && !(game.with(GameState::is_finished) && concealed.with(Vec::is_empty))
+    });
+
+    // 多人場次：再玩一次時換下一位
+    let (session, set_session) = signal(None::<Session>);
+    let current_player = Signal::derive(move || {
+        session.with(|s| {
+            s.as_ref().map(|s| match s.current_player() {
+                Some(name) => format!("輪到：{}", name),
+                None => "場次結束".to_string(),
+            })
+        })
     });
 
     let on_reset = Callback::new(move |_: ()| {
-        set_selected_numbers.set(Vec::new());
-        set_game_finished.set(false);
+        set_game.update(|g| g.reset());
+        set_undo_history.update(UndoHistory::clear);
+        if fair.get_untracked() {
+            start_fair_round();
+        }
+        if session.with_untracked(Option::is_some) {
+            set_session.update(|s| s.iter_mut().for_each(Session::advance));
+        }
+        if budget.with_untracked(Option::is_some) {
+            set_budget.update(|b| b.iter_mut().for_each(Budget::advance));
+        }
+        if envelope.with_untracked(Option::is_some) {
+            set_envelope.update(|e| e.iter_mut().for_each(RedEnvelope::advance));
+        }
     });
 
-    let on_no_repeat_change = Callback::new(move |_: bool| {
-        set_selected_numbers.set(Vec::new());
-        set_game_finished.set(false);
+    // 本局各位數選取當下的九宮格排列（鍵為位數，0 為個位數）
+    let (pick_layouts, set_pick_layouts) = signal(BTreeMap::<usize, Vec<u8>>::new());
+    let on_press = Callback::new(move |press: PadPress| {
+        if undo_enabled.get_untracked() {
+            set_undo_history.update(|h| {
+                h.record(Snapshot {
+                    game: press.before,
+                    grid: press.grid_before,
+                    transform: grid_transform.get_untracked(),
+                    pick_layouts: pick_layouts.get_untracked(),
+                })
+            });
+        }
+        match press.event {
+            Event::Picked { pos, .. } => set_pick_layouts.update(|l| {
+                l.insert(pos, press.layout);
+            }),
+            Event::Removed { pos, .. } => set_pick_layouts.update(|l| {
+                l.remove(&pos);
+            }),
+            Event::Tile(_) => {}
+        }
+    });
+
+    // 復原／重做：還原遊戲狀態、九宮格排列與 transform
+    let current_snapshot = move || Snapshot {
+        game: game.get_untracked(),
+        grid: grid_numbers.get_untracked(),
+        transform: grid_transform.get_untracked(),
+        pick_layouts: pick_layouts.get_untracked(),
+    };
+    let restore = move |snapshot: Snapshot| {
+        animator.cancel();
+        set_grid_numbers.set(snapshot.grid);
+        set_grid_transform.set(snapshot.transform);
+        set_pick_layouts.set(snapshot.pick_layouts);
+        set_game.set(snapshot.game);
+    };
+    let on_undo = Callback::new(move |_: ()| {
+        let mut restored = None;
+        set_undo_history.update(|h| restored = h.undo(current_snapshot()));
+        if let Some(snapshot) = restored {
+            restore(snapshot);
+        }
+    });
+    let on_redo = Callback::new(move |_: ()| {
+        let mut restored = None;
+        set_undo_history.update(|h| restored = h.redo(current_snapshot()));
+        if let Some(snapshot) = restored {
+            restore(snapshot);
+        }
+    });
+
+    // Ctrl+Z（或 Backspace）復原、Ctrl+Y（或 Ctrl+Shift+Z）重做；輸入框內保留瀏覽器原本的行為
+    let keydown = window_event_listener(ev::keydown, move |ev| {
+        if !undo_enabled.get_untracked() || typing_in_field(&ev) {
+            return;
+        }
+        let ctrl = ev.ctrl_key() || ev.meta_key();
+        match ev.key().to_lowercase().as_str() {
+            "z" if ctrl && ev.shift_key() => on_redo.run(()),
+            "z" if ctrl => on_undo.run(()),
+            "y" if ctrl => on_redo.run(()),
+            "backspace" if !ctrl => on_undo.run(()),
+            _ => return,
+        }
+        ev.prevent_default();
+    });
+    on_cleanup(move || keydown.remove());
+
+    let on_action = Callback::new(move |action: PadAction| {
+        if fair.get_untracked() {
+            set_fair_round.update(|r| r.actions.push(action));
+        }
     });
 
-    let (digits_needed_read, set_digits_needed_read) = signal(config.digits);
+    // 列數與欄數會影響之後的轉動結果，變更時記入操作紀錄
+    Effect::new(move |prev: Option<PadLayout>| {
+        let current = pad_layout.get();
+        if prev.is_some_and(|p| p != current) {
+            on_action.run(PadAction::Layout(current));
+        }
+        current
+    });
+
+    // 開獎後公開的驗證資料
+    let fair_proof = Memo::new(move |_| {
+        if !fair.get() {
+            return None;
+        }
+        let state = game.get();
+        if !state.is_finished() {
+            return None;
+        }
+        fair_round.with(|round| Some(round.proof(&state)))
+    });
+
+    // 每局開獎後寫入一筆紀錄
+    let (history, set_history) = signal(load_history());
+    let round_recorded = StoredValue::new(false);
     Effect::new(move |_| {
-        set_digits_needed_read.set(digits_needed.get());
+        let state = game.get();
+        if state.is_empty() {
+            round_recorded.set_value(false);
+            set_pick_layouts.set(BTreeMap::new());
+            // 紅包這份只有一種金額（例如最後一份），或範圍內的金額用可用數字抽不出來時不用抽，
+            // 新的一局直接填入，避免九宮格沒有可選的格子而卡住
+            let forced = envelope.with_untracked(|e| {
+                let e = e.as_ref()?;
+                e.forced_amount().or_else(|| {
+                    let (lo, _) = e.bounds()?;
+                    (!state.rules().is_feasible()).then_some(lo)
+                })
+            });
+            if let Some(amount) = forced {
+                set_game.set(GameState::with_amount(state.rules().clone(), amount));
+            }
+            return;
+        }
+        if !state.is_finished() || round_recorded.get_value() {
+            return;
+        }
+        let hidden = hidden_slots.with(|h| !h.is_empty());
+        let rules = state.rules();
+        let amount = state.payout();
+        let fair_fields = fair.get_untracked().then(|| {
+            fair_round.with_untracked(|r| {
+                (
+                    r.commitment.clone(),
+                    r.nonce.clone(),
+                    actions_to_string(&r.actions),
+                )
+            })
+        });
+        let (commitment, nonce, actions) = match fair_fields {
+            Some((c, n, a)) => (Some(c), Some(n), a),
+            None => (None, None, String::new()),
+        };
+        let record = RoundRecord {
+            timestamp: now_iso(),
+            amount,
+            digits: state.slots().iter().flatten().copied().collect(),
+            layouts: pick_layouts.get_untracked().into_values().collect(),
+            fill_order: rules.fill_order.to_query().to_string(),
+            pick_order: state.pick_order().to_vec(),
+            no_repeat: rules.no_repeat,
+            hidden_mode: hidden,
+            hidden_policy: hidden_policy.get_untracked().to_query(),
+            face_down: face_down.get_untracked(),
+            commitment,
+            nonce,
+            actions,
+            digits_needed: rules.digits_needed,
+            min_amount: rules.min_amount,
+            max_amount: rules.max_amount,
+            alphabet: rules.alphabet.to_query(),
+            seed: pad_seed.get_untracked(),
+            weights: weights.get_untracked().to_query(),
+            tiles: SpecialTiles(state.tiles_used().to_vec()).to_query(),
+        };
+        set_history.update(|h| {
+            h.push(record);
+            save_history(h);
+        });
+        round_recorded.set_value(true);
+        if session.with_untracked(Option::is_some) {
+            set_session.update(|s| s.iter_mut().for_each(|s| s.record(amount)));
+        }
+        if budget.with_untracked(Option::is_some) {
+            set_budget.update(|b| b.iter_mut().for_each(|b| b.record(amount)));
+        }
+        if envelope.with_untracked(Option::is_some) {
+            set_envelope.update(|e| e.iter_mut().for_each(|e| e.record(amount)));
+        }
     });
 
     view! {
         <main class="container">
+            {move || {
+                let errors = config_errors.get();
+                (!errors.is_empty())
+                    .then(|| {
+                        view! {
+                            <div class="config-warning">
+                                {format!("網址參數無效，已使用預設值：{}", errors.join(", "))}
+                            </div>
+                        }
+                    })
+            }}
+
             <AmountDisplay
-                selected_numbers=selected_numbers
-                digits_needed=digits_needed_read
-                hidden_mode=hidden_mode
-                game_finished=game_finished
-                set_game_finished=set_game_finished
+                game=game
+                set_game=set_game
+                hidden_slots=hidden_slots
+                concealed=concealed
+                set_revealed=set_revealed
+                current_player=current_player
+                speed=motion
                 on_reset=on_reset
             />
 
             <NumberPad
-                mode=mode
-                digits_needed=digits_needed_read
-                max_amount=max_amount
-                selected_numbers=selected_numbers
-                set_selected_numbers=set_selected_numbers
-                hidden_mode=hidden_mode
-                no_repeat=no_repeat
-                game_finished=game_finished
+                game=game
+                set_game=set_game
+                masked=masked
+                locked=pad_locked
+                face_down=face_down
+                seed=pad_seed
+                grid_numbers=grid_numbers
+                set_grid_numbers=set_grid_numbers
+                grid_transform=grid_transform
+                set_grid_transform=set_grid_transform
+                pad_size=pad_size
+                set_pad_size=set_pad_size
+                layout=pad_layout
+                set_layout=set_layout
+                weights=weights
+                spin_style=spin_style
+                speed=motion
+                animator=animator
+                on_press=on_press
+                on_action=on_action
             />
 
+            <Show when=move || undo_enabled.get()>
+                <UndoButtons history=undo_history on_undo=on_undo on_redo=on_redo />
+            </Show>
+
             <GameOptions
                 no_repeat=no_repeat
                 set_no_repeat=set_no_repeat
-                hidden_mode=hidden_mode
-                set_hidden_mode=set_hidden_mode
-                on_no_repeat_change=on_no_repeat_change
+                no_undo=no_undo
+                set_no_undo=set_no_undo
+                fair=fair
+                set_fair=set_fair
+                face_down=face_down
+                set_face_down=set_face_down
+                fill_order=fill_order
+                set_fill_order=set_fill_order
+                alphabet=alphabet
+                set_alphabet=set_alphabet
+                spin_style=spin_style
+                set_spin_style=set_spin_style
+                speed=speed
+                set_speed=set_speed
+                specials=specials
+                set_specials=set_specials
+                consolation=consolation
+                set_consolation=set_consolation
+                on_seed_change=on_seed_change
+                seed_used=seed_used
+            />
+
+            <HiddenOptions
+                policy=hidden_policy
+                set_policy=set_hidden_policy
+                digits_needed=Signal::derive(move || rules.with(|r| r.digits_needed))
+            />
+
+            <Show when=move || fair.get()>
+                <FairPanel round=fair_round proof=fair_proof />
+            </Show>
+
+            <DigitWeightsPanel
+                alphabet=alphabet
+                weights=weights
+                set_weights=set_weights
+                rules=rules
             />
 
             <RangeConfig
@@ -155,7 +609,20 @@ pub fn App() -> impl IntoView {
                 set_selected_digits=set_selected_digits
                 max_amount=max_amount
                 set_max_amount=set_max_amount
+                min_amount=min_amount
+                set_min_amount=set_min_amount
+                infeasible=infeasible
             />
+
+            <AnalysisPanel rules=rules />
+
+            <SessionPanel session=session set_session=set_session />
+
+            <BudgetPanel budget=budget set_budget=set_budget cap=max_cap />
+
+            <RedEnvelopePanel envelope=envelope set_envelope=set_envelope />
+
+            <HistoryPanel history=history set_history=set_history />
         </main>
     }
 }
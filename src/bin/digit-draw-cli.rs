@@ -0,0 +1,293 @@
+//! 命令列版：在終端機以數字鍵盤進行與網頁版相同規則的抽選，可透過 SSH 使用或寫成腳本。
+//! 盤面與提示輸出到 stderr，stdout 只有最後的結果
+
+use std::io::{self, BufRead};
+use std::process::ExitCode;
+
+use clap::Parser;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+
+use digit_draw::analysis::{analyze, Analysis, SIMULATION_ROUNDS};
+use digit_draw::engine::{
+    cell_label, DigitAlphabet, Event, GameRules, GameState, HiddenPolicy, RejectReason,
+    SpecialTile, SpecialTiles,
+};
+use digit_draw::pad::{self, PadLayout};
+
+const DEFAULT_DIGITS: u8 = 4;
+
+#[derive(Parser)]
+#[command(name = "digit-draw-cli", about = "數字抽選遊戲（命令列版）")]
+struct Args {
+    /// 位數（1-9），預設 4
+    #[arg(long, conflicts_with = "max", value_parser = clap::value_parser!(u8).range(1..=9))]
+    digits: Option<u8>,
+    /// 最大金額
+    #[arg(long)]
+    max: Option<u64>,
+    /// 最小金額（與 --max 一起使用時為金額範圍模式）
+    #[arg(long, requires = "max")]
+    min: Option<u64>,
+    /// 不允許重複
+    #[arg(long)]
+    no_repeat: bool,
+    /// 隱藏模式：1（最高位）、all、pos:0,2（指定位數）、random:2（隨機隱藏 2 位）
+    #[arg(long, value_parser = parse_hidden)]
+    hidden: Option<HiddenPolicy>,
+    /// 九宮格的列數與欄數，例如 2x5；預設 3 欄
+    #[arg(long, value_parser = parse_layout)]
+    layout: Option<PadLayout>,
+    /// 混在九宮格中的特殊格，例如 x2,bust,redraw,free
+    #[arg(long, value_parser = parse_special)]
+    special: Option<SpecialTiles>,
+    /// 按到「爆」時的安慰獎
+    #[arg(long, default_value_t = 0)]
+    consolation: u64,
+    /// 種子；省略時隨機產生
+    #[arg(long)]
+    seed: Option<u64>,
+    /// 自動抽選直到填滿，不讀取鍵盤輸入
+    #[arg(long)]
+    auto: bool,
+    /// 只分析這個設定的金額分佈，不進行抽選
+    #[arg(long)]
+    analyze: bool,
+    /// 以 JSON 輸出結果
+    #[arg(long)]
+    json: bool,
+}
+
+fn parse_hidden(s: &str) -> Result<HiddenPolicy, String> {
+    HiddenPolicy::parse(s).ok_or_else(|| format!("無效的隱藏模式：{}", s))
+}
+
+fn parse_layout(s: &str) -> Result<PadLayout, String> {
+    PadLayout::parse(s).ok_or_else(|| format!("無效的列數與欄數：{}", s))
+}
+
+fn parse_special(s: &str) -> Result<SpecialTiles, String> {
+    SpecialTiles::parse(s).ok_or_else(|| format!("無效的特殊格：{}", s))
+}
+
+/// 輸出的抽選結果
+#[derive(Serialize)]
+struct DrawResult {
+    /// 開獎金額（含特殊格的效果）
+    amount: u64,
+    /// 各位數的數字，索引 0 為個位數
+    digits: Vec<u8>,
+    /// 依選取順序填入的位數
+    pick_order: Vec<usize>,
+    /// 開獎前隱藏的位數
+    hidden: Vec<usize>,
+    /// 依按下順序用過的特殊格
+    tiles: Vec<&'static str>,
+    seed: u64,
+}
+
+fn reject_message(reason: RejectReason) -> &'static str {
+    match reason {
+        RejectReason::Finished => "已開獎",
+        RejectReason::Full => "位數已填滿",
+        RejectReason::NoSlot => "沒有可以填入的位數",
+        RejectReason::NotInAlphabet => "不在可用數字中",
+        RejectReason::Repeated => "數字已選過",
+        RejectReason::LeadingZero => "最高位不可為 0",
+        RejectReason::ExceedsLimit => "會超出金額範圍",
+        RejectReason::TileUsed => "特殊格已使用",
+        RejectReason::Locked => "不能收回已填入的數字",
+    }
+}
+
+/// 數字鍵對應的格子位置，見 [`pad::numpad_position`]
+fn key_position(key: char, len: usize, layout: PadLayout) -> Option<usize> {
+    let (row, col) = pad::numpad_position(key.to_digit(10)?)?;
+    pad::cell_at(len, layout, 0, row, col)
+}
+
+/// 九宮格：已選的數字（與用過的特殊格）加上括號，不可選的以 `·` 顯示；隱藏模式下都以 `$` 顯示
+fn render_pad(state: &GameState, grid: &[u8], layout: PadLayout, masked: bool) -> String {
+    grid.chunks(layout.cols)
+        .map(|row| {
+            row.iter()
+                .map(|&d| {
+                    let cell = state.cell_state(d);
+                    let label = if masked {
+                        "$".to_string()
+                    } else {
+                        cell_label(d)
+                    };
+                    if cell.selected && !masked {
+                        format!("[{}]", label)
+                    } else if cell.disabled {
+                        " · ".to_string()
+                    } else {
+                        format!(" {} ", label)
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 金額由高位到低位，隱藏的位數以 `$`、空位以 `_` 顯示
+fn render_amount(state: &GameState, hidden: &[usize]) -> String {
+    state
+        .slots()
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(pos, slot)| match slot {
+            _ if hidden.contains(&pos) => '$',
+            Some(d) => char::from(b'0' + d),
+            None => '_',
+        })
+        .collect()
+}
+
+fn print_analysis(analysis: &Analysis) {
+    if analysis.exact {
+        println!("精確計算");
+    } else {
+        println!("模擬 {} 局估計", SIMULATION_ROUNDS);
+    }
+    println!(
+        "最小：{}　最大：{}　平均：{:.1}",
+        analysis.min, analysis.max, analysis.mean
+    );
+    for &(q, amount) in &analysis.percentiles {
+        println!("P{}：{}", q, amount);
+    }
+    for &(start, end, p) in &analysis.histogram {
+        println!("{:>9}-{:<9} {:>5.1}%", start, end, p * 100.0);
+    }
+}
+
+/// 按下一格；「重抽」特殊格重新洗牌
+fn press(state: &mut GameState, grid: &mut [u8], digit: u8, rng: &mut ChaCha8Rng) {
+    match state.press(digit, rng) {
+        Ok(Event::Tile(SpecialTile::Redraw)) => {
+            pad::shuffle(grid, rng);
+            eprintln!("重新洗牌");
+        }
+        Ok(Event::Tile(SpecialTile::Bust)) => eprintln!("爆！"),
+        Ok(_) => {}
+        Err(reason) => eprintln!("無法選取：{}", reject_message(reason)),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let alphabet = DigitAlphabet::default();
+    let mut rules = match (args.min, args.max) {
+        (Some(min), Some(max)) => GameRules::by_range(min, max, args.no_repeat, alphabet),
+        (None, Some(max)) => GameRules::by_max_amount(max, args.no_repeat, alphabet),
+        _ => GameRules::by_digits(
+            args.digits.unwrap_or(DEFAULT_DIGITS),
+            args.no_repeat,
+            alphabet,
+        ),
+    };
+    rules.consolation = args.consolation;
+    if !rules.is_feasible() {
+        eprintln!("金額範圍內沒有可以抽出的金額");
+        return ExitCode::FAILURE;
+    }
+
+    let seed = args.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    if args.analyze {
+        let Some(analysis) = analyze(&rules, &mut rng) else {
+            return ExitCode::FAILURE;
+        };
+        if args.json {
+            println!("{}", serde_json::to_string(&analysis).unwrap_or_default());
+        } else {
+            print_analysis(&analysis);
+        }
+        return ExitCode::SUCCESS;
+    }
+    // 與網頁版相同，隱藏位數使用另一條亂數串流
+    let hidden = args
+        .hidden
+        .unwrap_or_default()
+        .hidden_slots(rules.digits_needed, &mut HiddenPolicy::rng(seed));
+    let masked = !hidden.is_empty();
+    let mut grid = args.special.unwrap_or_default().pad_cells(&rules.alphabet);
+    let layout = match args.layout {
+        Some(layout) if !layout.fits(grid.len()) => {
+            eprintln!("{} 放不下 {} 格", layout.label(), grid.len());
+            return ExitCode::FAILURE;
+        }
+        Some(layout) => layout,
+        None => PadLayout::auto(grid.len()),
+    };
+    let mut state = GameState::new(rules);
+
+    if args.auto {
+        while !state.is_complete() && !state.is_finished() {
+            match pad::auto_choice(&state, &grid, &mut rng) {
+                Some(digit) => press(&mut state, &mut grid, digit, &mut rng),
+                None => break,
+            }
+        }
+    } else {
+        eprintln!("數字鍵依位置選取格子（7-8-9／4-5-6／1-2-3），s 隨機位置，a 自動抽選，q 離開");
+        let mut lines = io::stdin().lock().lines();
+        'input: while !state.is_complete() && !state.is_finished() {
+            eprintln!(
+                "\n{}\n金額：{}",
+                render_pad(&state, &grid, layout, masked),
+                render_amount(&state, &hidden)
+            );
+            let Some(Ok(line)) = lines.next() else {
+                break;
+            };
+            for key in line.chars() {
+                match key {
+                    's' | 'S' => pad::shuffle(&mut grid, &mut rng),
+                    'a' | 'A' => {
+                        if let Some(digit) = pad::auto_choice(&state, &grid, &mut rng) {
+                            press(&mut state, &mut grid, digit, &mut rng);
+                        }
+                    }
+                    'q' | 'Q' => break 'input,
+                    _ => {
+                        match key_position(key, grid.len(), layout).and_then(|pos| grid.get(pos)) {
+                            Some(&digit) => press(&mut state, &mut grid, digit, &mut rng),
+                            None if key.is_whitespace() => {}
+                            None => eprintln!("無效的按鍵：{}", key),
+                        }
+                    }
+                }
+                if state.is_complete() || state.is_finished() {
+                    break;
+                }
+            }
+        }
+    }
+
+    if !state.finish() {
+        eprintln!("尚未填滿，已取消");
+        return ExitCode::FAILURE;
+    }
+    let result = DrawResult {
+        amount: state.payout(),
+        digits: state.slots().iter().flatten().copied().collect(),
+        pick_order: state.pick_order().to_vec(),
+        hidden,
+        tiles: state.tiles_used().iter().map(|t| t.to_query()).collect(),
+        seed,
+    };
+    if args.json {
+        println!("{}", serde_json::to_string(&result).unwrap_or_default());
+    } else {
+        println!("金額：{}", result.amount);
+        println!("種子：{}", result.seed);
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,146 @@
+use leptos::prelude::*;
+
+/// 固定總額的獎金預算：每局開獎後扣除抽出的金額
+#[derive(Clone, PartialEq)]
+pub struct Budget {
+    pub total: u64,
+    /// 目前局數
+    turn: usize,
+    /// 各局抽出的金額，索引為局數
+    results: Vec<u64>,
+}
+
+impl Budget {
+    pub fn new(total: u64) -> Self {
+        Self {
+            total,
+            turn: 0,
+            results: Vec::new(),
+        }
+    }
+
+    /// 已發出的金額（含目前這局）
+    pub fn spent(&self) -> u64 {
+        self.results.iter().sum()
+    }
+
+    /// 剩餘預算（含目前這局）
+    pub fn remaining(&self) -> u64 {
+        self.total.saturating_sub(self.spent())
+    }
+
+    /// 目前這局可用的預算：扣除之前各局，不含目前這局的結果
+    pub fn available(&self) -> u64 {
+        let spent: u64 = self.results.iter().take(self.turn).sum();
+        self.total.saturating_sub(spent)
+    }
+
+    /// 已抽出結果的局數
+    pub fn rounds(&self) -> usize {
+        self.results.len()
+    }
+
+    /// 預算已用完，不能再開始新的一局
+    pub fn is_exhausted(&self) -> bool {
+        self.available() == 0
+    }
+
+    /// 記錄目前這局的結果；同一局重複記錄時覆蓋
+    pub fn record(&mut self, amount: u64) {
+        if self.is_exhausted() {
+            return;
+        }
+        if self.results.len() > self.turn {
+            self.results[self.turn] = amount;
+        } else {
+            self.results.push(amount);
+        }
+    }
+
+    /// 目前這局已有結果時換下一局
+    pub fn advance(&mut self) {
+        if self.results.len() > self.turn {
+            self.turn += 1;
+        }
+    }
+}
+
+#[component]
+pub fn BudgetPanel(
+    budget: ReadSignal<Option<Budget>>,
+    set_budget: WriteSignal<Option<Budget>>,
+    /// 選擇最大金額時，本局實際的最大金額
+    cap: Signal<Option<u64>>,
+) -> impl IntoView {
+    let (total, set_total) = signal(10_000u64);
+
+    let on_start = move |_| {
+        let total = total.get();
+        if total > 0 {
+            set_budget.set(Some(Budget::new(total)));
+        }
+    };
+
+    view! {
+        <div class="budget-panel">
+            <h2>"獎金預算"</h2>
+            {move || match budget.get() {
+                None => {
+                    view! {
+                        <div class="budget-setup">
+                            <label>"總預算: "</label>
+                            <input
+                                type="number"
+                                min="1"
+                                prop:value=move || total.get().to_string()
+                                on:input=move |ev| {
+                                    let val = event_target_value(&ev).parse::<u64>().unwrap_or(0);
+                                    set_total.set(val);
+                                }
+                            />
+                            <button class="option-btn" on:click=on_start>
+                                "開始預算"
+                            </button>
+                        </div>
+                    }
+                        .into_any()
+                }
+                Some(b) => {
+                    view! {
+                        <div class="budget-board">
+                            <div class="budget-row">
+                                <span>"總預算"</span>
+                                <span class="budget-value">{b.total.to_string()}</span>
+                            </div>
+                            <div class="budget-row">
+                                <span>{format!("已發出（{} 局）", b.rounds())}</span>
+                                <span class="budget-value">{b.spent().to_string()}</span>
+                            </div>
+                            <div class="budget-row budget-remaining">
+                                <span>"剩餘"</span>
+                                <span class="budget-value">{b.remaining().to_string()}</span>
+                            </div>
+                            {if b.is_exhausted() {
+                                view! { <p class="budget-exhausted">"預算已用完"</p> }.into_any()
+                            } else {
+                                cap.get()
+                                    .map(|c| {
+                                        view! {
+                                            <p class="budget-cap">
+                                                {format!("本局最大金額：{}", c)}
+                                            </p>
+                                        }
+                                    })
+                                    .into_any()
+                            }}
+                            <button class="option-btn" on:click=move |_| set_budget.set(None)>
+                                "結束預算"
+                            </button>
+                        </div>
+                    }
+                        .into_any()
+                }
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,330 @@
+//! 遊戲設定與 URL query 之間的轉換
+
+use std::str::FromStr;
+
+use web_sys::UrlSearchParams;
+
+use crate::animation::AnimationSpeed;
+use crate::engine::{
+    cell_symbol, parse_cell_symbol, DigitAlphabet, DigitWeights, FillOrder, HiddenPolicy,
+    SpecialTiles,
+};
+use crate::pad::{PadLayout, SpinStyle, TRANSFORM_COUNT};
+use crate::range_config::LimitMode;
+
+const DEFAULT_DIGITS: u8 = 4;
+const DEFAULT_MAX_AMOUNT: u64 = 999;
+const DEFAULT_MIN_AMOUNT: u64 = 100;
+const DEFAULT_PAD_SIZE: u32 = 100;
+
+/// 可由 URL 分享的完整遊戲設定
+#[derive(Clone, PartialEq)]
+pub struct GameConfig {
+    pub mode: LimitMode,
+    pub digits: u8,
+    pub max_amount: u64,
+    /// 金額範圍模式的下限
+    pub min_amount: u64,
+    pub no_repeat: bool,
+    pub hidden: HiddenPolicy,
+    /// 關閉復原／重做
+    pub no_undo: bool,
+    pub fill_order: FillOrder,
+    /// 翻牌模式
+    pub face_down: bool,
+    /// 公正驗證
+    pub fair: bool,
+    /// 隨機轉動的方式
+    pub spin_style: SpinStyle,
+    /// 混在九宮格中的特殊格
+    pub specials: SpecialTiles,
+    /// 按到「爆」時的安慰獎
+    pub consolation: u64,
+    /// 動畫速度
+    pub speed: AnimationSpeed,
+    pub alphabet: DigitAlphabet,
+    pub seed: Option<u64>,
+    pub weights: DigitWeights,
+    /// 九宮格大小（50-100%）
+    pub pad_size: u32,
+    /// 九宮格排列；必須是 `alphabet` 加上 `specials` 的排列
+    pub grid: Vec<u8>,
+    /// 九宮格 transform 索引
+    pub transform: usize,
+    /// 九宮格的列數與欄數；`None` 為預設排法
+    pub layout: Option<PadLayout>,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        let alphabet = DigitAlphabet::default();
+        Self {
+            mode: LimitMode::ByDigits,
+            digits: DEFAULT_DIGITS,
+            max_amount: DEFAULT_MAX_AMOUNT,
+            min_amount: DEFAULT_MIN_AMOUNT,
+            no_repeat: false,
+            hidden: HiddenPolicy::default(),
+            no_undo: false,
+            fill_order: FillOrder::default(),
+            face_down: false,
+            fair: false,
+            spin_style: SpinStyle::default(),
+            specials: SpecialTiles::default(),
+            consolation: 0,
+            speed: AnimationSpeed::default(),
+            grid: alphabet.digits.clone(),
+            alphabet,
+            seed: None,
+            weights: DigitWeights::default(),
+            pad_size: DEFAULT_PAD_SIZE,
+            transform: 0,
+            layout: None,
+        }
+    }
+}
+
+/// `grid` 是否為 `cells`（可用數字與特殊格，見 [`SpecialTiles::pad_cells`]）的一種排列
+pub fn is_layout_of(grid: &[u8], cells: &[u8]) -> bool {
+    let mut sorted = grid.to_vec();
+    sorted.sort_unstable();
+    sorted == cells
+}
+
+impl GameConfig {
+    /// 除了九宮格的排列、transform、大小、列數與欄數及動畫速度以外的設定都相同
+    fn same_game(&self, other: &Self) -> bool {
+        self.mode == other.mode
+            && self.digits == other.digits
+            && self.max_amount == other.max_amount
+            && self.min_amount == other.min_amount
+            && self.no_repeat == other.no_repeat
+            && self.hidden == other.hidden
+            && self.no_undo == other.no_undo
+            && self.fill_order == other.fill_order
+            && self.face_down == other.face_down
+            && self.fair == other.fair
+            && self.spin_style == other.spin_style
+            && self.specials == other.specials
+            && self.consolation == other.consolation
+            && self.alphabet == other.alphabet
+            && self.seed == other.seed
+            && self.weights == other.weights
+    }
+
+    /// 轉成 URL query；與預設相同的選項省略
+    pub fn to_query(&self) -> String {
+        let mut query = match self.mode {
+            LimitMode::ByDigits => format!("?digits={}", self.digits),
+            LimitMode::ByMaxAmount => format!("?max={}", self.max_amount),
+            LimitMode::ByRange => format!("?min={}&max={}", self.min_amount, self.max_amount),
+        };
+        if self.no_repeat {
+            query.push_str("&norepeat=1");
+        }
+        if self.hidden != HiddenPolicy::Off {
+            query.push_str(&format!("&hidden={}", self.hidden.to_query()));
+        }
+        if self.no_undo {
+            query.push_str("&noundo=1");
+        }
+        if self.face_down {
+            query.push_str("&facedown=1");
+        }
+        if self.fair {
+            query.push_str("&fair=1");
+        }
+        if self.spin_style != SpinStyle::default() {
+            query.push_str(&format!("&spin={}", self.spin_style.to_query()));
+        }
+        if !self.specials.0.is_empty() {
+            query.push_str(&format!("&special={}", self.specials.to_query()));
+        }
+        if self.consolation != 0 {
+            query.push_str(&format!("&consolation={}", self.consolation));
+        }
+        if self.speed != AnimationSpeed::default() {
+            query.push_str(&format!("&anim={}", self.speed.to_query()));
+        }
+        if self.fill_order != FillOrder::default() {
+            query.push_str(&format!("&fill={}", self.fill_order.to_query()));
+        }
+        if self.alphabet != DigitAlphabet::default() {
+            query.push_str(&format!("&alphabet={}", self.alphabet.to_query()));
+            if self.alphabet.leading_zero {
+                query.push_str("&lead0=1");
+            }
+        }
+        if let Some(seed) = self.seed {
+            query.push_str(&format!("&seed={}", seed));
+        }
+        if self.weights != DigitWeights::default() {
+            query.push_str(&format!("&weights={}", self.weights.to_query()));
+        }
+        if self.pad_size != DEFAULT_PAD_SIZE {
+            query.push_str(&format!("&size={}", self.pad_size));
+        }
+        if self.grid != self.specials.pad_cells(&self.alphabet) {
+            let grid: String = self.grid.iter().map(|&d| cell_symbol(d)).collect();
+            query.push_str(&format!("&grid={}", grid));
+        }
+        if self.transform != 0 {
+            query.push_str(&format!("&tf={}", self.transform));
+        }
+        if let Some(layout) = self.layout {
+            query.push_str(&format!("&layout={}", layout.to_query()));
+        }
+        query
+    }
+}
+
+/// 讀取並驗證單一參數；格式錯誤或不合法時記錄參數名稱並回傳 `None`
+fn param<T: FromStr>(
+    p: &UrlSearchParams,
+    key: &'static str,
+    valid: impl Fn(&T) -> bool,
+    errors: &mut Vec<&'static str>,
+) -> Option<T> {
+    let raw = p.get(key)?;
+    match raw.parse::<T>() {
+        Ok(v) if valid(&v) => Some(v),
+        _ => {
+            errors.push(key);
+            None
+        }
+    }
+}
+
+/// 讀取開關參數（"1" 或 "0"）
+fn flag(p: &UrlSearchParams, key: &'static str, errors: &mut Vec<&'static str>) -> bool {
+    param::<u8>(p, key, |v| *v <= 1, errors) == Some(1)
+}
+
+/// 從目前網址讀取設定；無效的參數改用預設值，並回傳無效的參數名稱
+pub fn read_config_from_url() -> (GameConfig, Vec<&'static str>) {
+    let params = web_sys::window()
+        .and_then(|w| w.location().search().ok())
+        .and_then(|s| UrlSearchParams::new_with_str(&s).ok());
+
+    let Some(p) = params else {
+        return (GameConfig::default(), Vec::new());
+    };
+
+    let mut errors = Vec::new();
+    let mut config = GameConfig::default();
+
+    if let Some(d) = param::<u8>(&p, "digits", |_| true, &mut errors) {
+        config.mode = LimitMode::ByDigits;
+        config.digits = d.clamp(1, 9);
+    } else if let Some(m) = param::<u64>(&p, "max", |_| true, &mut errors) {
+        config.mode = LimitMode::ByMaxAmount;
+        config.max_amount = m.max(1);
+        if let Some(min) = param::<u64>(&p, "min", |v| *v <= m, &mut errors) {
+            config.mode = LimitMode::ByRange;
+            config.min_amount = min;
+        }
+    }
+
+    config.no_repeat = flag(&p, "norepeat", &mut errors);
+    if let Some(raw) = p.get("hidden") {
+        match HiddenPolicy::parse(&raw) {
+            Some(policy) => config.hidden = policy,
+            None => errors.push("hidden"),
+        }
+    }
+    config.no_undo = flag(&p, "noundo", &mut errors);
+    config.face_down = flag(&p, "facedown", &mut errors);
+    config.fair = flag(&p, "fair", &mut errors);
+    if let Some(raw) = p.get("spin") {
+        match SpinStyle::parse(&raw) {
+            Some(style) => config.spin_style = style,
+            None => errors.push("spin"),
+        }
+    }
+    if let Some(raw) = p.get("special") {
+        match SpecialTiles::parse(&raw) {
+            Some(specials) => config.specials = specials,
+            None => errors.push("special"),
+        }
+    }
+    if let Some(c) = param::<u64>(&p, "consolation", |_| true, &mut errors) {
+        config.consolation = c;
+    }
+    if let Some(raw) = p.get("anim") {
+        match AnimationSpeed::parse(&raw) {
+            Some(speed) => config.speed = speed,
+            None => errors.push("anim"),
+        }
+    }
+    if let Some(raw) = p.get("fill") {
+        match FillOrder::parse(&raw) {
+            Some(order) => config.fill_order = order,
+            None => errors.push("fill"),
+        }
+    }
+
+    let leading_zero = flag(&p, "lead0", &mut errors);
+    if let Some(raw) = p.get("alphabet") {
+        match DigitAlphabet::parse(&raw, leading_zero) {
+            Some(alphabet) => config.alphabet = alphabet,
+            None => errors.push("alphabet"),
+        }
+    }
+    let cells = config.specials.pad_cells(&config.alphabet);
+    config.grid = cells.clone();
+
+    config.seed = param::<u64>(&p, "seed", |_| true, &mut errors);
+    if let Some(raw) = p.get("weights") {
+        match DigitWeights::parse(&raw) {
+            Some(weights) => config.weights = weights,
+            None => errors.push("weights"),
+        }
+    }
+    if let Some(size) = param::<u32>(&p, "size", |v| (50..=100).contains(v), &mut errors) {
+        config.pad_size = size;
+    }
+    if let Some(raw) = p.get("grid") {
+        let grid: Option<Vec<u8>> = raw.chars().map(parse_cell_symbol).collect();
+        match grid.filter(|g| is_layout_of(g, &cells)) {
+            Some(grid) => config.grid = grid,
+            None => errors.push("grid"),
+        }
+    }
+    if let Some(tf) = param::<usize>(&p, "tf", |v| *v < TRANSFORM_COUNT, &mut errors) {
+        config.transform = tf;
+    }
+    if let Some(raw) = p.get("layout") {
+        match PadLayout::parse(&raw).filter(|l| l.fits(cells.len())) {
+            Some(layout) => config.layout = Some(layout),
+            None => errors.push("layout"),
+        }
+    }
+
+    (config, errors)
+}
+
+/// 將設定寫入網址；遊戲設定變更時新增瀏覽紀錄（可用上一頁還原），
+/// 只有九宮格排列、transform、大小、列數與欄數或動畫速度變更時取代目前紀錄，避免每次洗牌都多一筆
+pub fn sync_url_query(config: &GameConfig, previous: Option<&GameConfig>) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let location = window.location();
+    let Ok(pathname) = location.pathname() else {
+        return;
+    };
+    let query = config.to_query();
+    if location.search().ok().as_deref() == Some(query.as_str()) {
+        return;
+    }
+    let url = format!("{}{}", pathname, query);
+    let Ok(history) = window.history() else {
+        return;
+    };
+    let push = previous.is_some_and(|prev| !prev.same_game(config));
+    let _ = if push {
+        history.push_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url))
+    } else {
+        history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url))
+    };
+}
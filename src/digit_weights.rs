@@ -0,0 +1,86 @@
+use leptos::prelude::*;
+
+use crate::analysis::histogram;
+use crate::analysis_panel::DistributionBars;
+use crate::engine::{amount_distribution, DigitAlphabet, DigitWeights, GameRules, MAX_ENUM_DIGITS};
+
+#[component]
+pub fn DigitWeightsPanel(
+    alphabet: ReadSignal<DigitAlphabet>,
+    weights: ReadSignal<DigitWeights>,
+    set_weights: WriteSignal<DigitWeights>,
+    rules: Memo<GameRules>,
+) -> impl IntoView {
+    // 只有設定上限時才計算分佈
+    let distribution = Memo::new(move |_| {
+        let r = rules.get();
+        r.max_amount?;
+        Some(amount_distribution(&r, &weights.get()))
+    });
+
+    view! {
+        <div class="digit-weights">
+            <h2>"數字權重"</h2>
+            <p class="digit-weights-hint">"隱藏模式下，點選的格子依權重決定翻開的數字"</p>
+            <div class="digit-weights-grid">
+                {move || {
+                    alphabet
+                        .get()
+                        .digits
+                        .into_iter()
+                        .map(|d| {
+                            view! {
+                                <label class="digit-weight">
+                                    <span>{d.to_string()}</span>
+                                    <input
+                                        type="number"
+                                        min="0"
+                                        max="9"
+                                        prop:value=move || weights.get().0[d as usize].to_string()
+                                        on:input=move |ev| {
+                                            let val = event_target_value(&ev)
+                                                .parse::<u8>()
+                                                .unwrap_or(1)
+                                                .min(9);
+                                            set_weights.update(|w| w.0[d as usize] = val);
+                                        }
+                                    />
+                                </label>
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                }}
+            </div>
+
+            {move || {
+                distribution
+                    .get()
+                    .map(|dist| match dist {
+                        None => {
+                            view! {
+                                <p class="digit-weights-hint">
+                                    {format!(
+                                        "超過 {} 位數、範圍無法完成或自選位數時不計算金額分佈",
+                                        MAX_ENUM_DIGITS,
+                                    )}
+                                </p>
+                            }
+                                .into_any()
+                        }
+                        Some(dist) => {
+                            let mean: f64 = dist.iter().map(|&(a, p)| a as f64 * p).sum();
+                            view! {
+                                <div class="distribution">
+                                    <p class="distribution-mean">
+                                        {format!("金額期望值：{:.1}", mean)}
+                                    </p>
+                                    <DistributionBars buckets=histogram(&dist) />
+                                </div>
+                            }
+                                .into_any()
+                        }
+                    })
+            }}
+        </div>
+    }
+}
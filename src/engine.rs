@@ -0,0 +1,1270 @@
+//! 遊戲規則：與 Leptos 元件無關，元件只負責呈現 [`GameState`] 的狀態
+
+use std::collections::HashMap;
+
+use rand::seq::{index, SliceRandom};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// 九宮格可用的數字組
+#[derive(Clone, PartialEq)]
+pub struct DigitAlphabet {
+    /// 由小到大、不重複的可用數字（0-9）
+    pub digits: Vec<u8>,
+    /// 最高位是否可以是 0
+    pub leading_zero: bool,
+}
+
+impl Default for DigitAlphabet {
+    fn default() -> Self {
+        Self {
+            digits: (1..=9).collect(),
+            leading_zero: false,
+        }
+    }
+}
+
+impl DigitAlphabet {
+    /// `digit` 能否放在第 `pos` 位（0 為個位數）
+    pub fn allows_at(&self, digit: u8, pos: usize, digits_needed: u8) -> bool {
+        digit != 0 || self.leading_zero || pos + 1 < digits_needed as usize
+    }
+
+    /// 由 URL 參數（例如 "0123456789"）解析，至少需包含一個非 0 數字
+    pub fn parse(s: &str, leading_zero: bool) -> Option<Self> {
+        let mut digits: Vec<u8> = s
+            .chars()
+            .map(|c| c.to_digit(10).map(|d| d as u8))
+            .collect::<Option<_>>()?;
+        digits.sort_unstable();
+        digits.dedup();
+        if !digits.iter().any(|&d| d != 0) {
+            return None;
+        }
+        Some(Self {
+            digits,
+            leading_zero,
+        })
+    }
+
+    /// 轉成 URL 參數字串
+    pub fn to_query(&self) -> String {
+        self.digits.iter().map(|d| d.to_string()).collect()
+    }
+
+    /// 切換某個數字是否可用；至少保留一個非 0 數字
+    pub fn toggle(&mut self, digit: u8) {
+        if let Some(idx) = self.digits.iter().position(|&d| d == digit) {
+            if digit != 0 && self.digits.iter().filter(|&&d| d != 0).count() <= 1 {
+                return;
+            }
+            self.digits.remove(idx);
+        } else {
+            self.digits.push(digit);
+            self.digits.sort_unstable();
+        }
+    }
+}
+
+/// 計算已填入的數字組成的金額（索引 0 為個位數，空位視為 0）
+fn current_amount(slots: &[Option<u8>]) -> u64 {
+    slots
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &n)| n.map(|n| n as u64 * 10u64.pow(i as u32)))
+        .sum()
+}
+
+/// 最大金額的位數，最多 9 位
+fn max_digits(max_amount: u64) -> u8 {
+    if max_amount == 0 {
+        1
+    } else {
+        ((max_amount as f64).log10().floor() as u8 + 1).min(9)
+    }
+}
+
+/// 數字填入位數的順序
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FillOrder {
+    /// 由個位數往高位填
+    #[default]
+    OnesFirst,
+    /// 由最高位往個位數填
+    HighFirst,
+    /// 每次選取時隨機決定填入哪一位
+    Random,
+    /// 玩家先點金額上的位數，再點九宮格
+    PlayerChosen,
+}
+
+impl FillOrder {
+    /// 所有填入順序，依選項顯示順序排列
+    pub const ALL: [FillOrder; 4] = [
+        FillOrder::OnesFirst,
+        FillOrder::HighFirst,
+        FillOrder::Random,
+        FillOrder::PlayerChosen,
+    ];
+
+    /// 由 URL 參數解析
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "ones" => Some(Self::OnesFirst),
+            "high" => Some(Self::HighFirst),
+            "random" => Some(Self::Random),
+            "pick" => Some(Self::PlayerChosen),
+            _ => None,
+        }
+    }
+
+    /// 轉成 URL 參數字串
+    pub fn to_query(self) -> &'static str {
+        match self {
+            Self::OnesFirst => "ones",
+            Self::HighFirst => "high",
+            Self::Random => "random",
+            Self::PlayerChosen => "pick",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::OnesFirst => "個位優先",
+            Self::HighFirst => "高位優先",
+            Self::Random => "隨機位數",
+            Self::PlayerChosen => "自選位數",
+        }
+    }
+}
+
+/// 混在九宮格中的特殊格；在九宮格排列中以 [`SpecialTile::code`]（10 以上）表示
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpecialTile {
+    /// 開獎金額加倍，不超過金額上限
+    Double,
+    /// 直接結束這一局，只拿安慰獎
+    Bust,
+    /// 重新洗牌，這次不算選取
+    Redraw,
+    /// 下一個數字可以自己點選要填入的位數
+    FreePick,
+}
+
+impl SpecialTile {
+    pub const ALL: [SpecialTile; 4] = [
+        SpecialTile::Double,
+        SpecialTile::Bust,
+        SpecialTile::Redraw,
+        SpecialTile::FreePick,
+    ];
+
+    /// 九宮格排列中的代碼；數字格為 0-9
+    pub fn code(self) -> u8 {
+        10 + Self::ALL.iter().position(|&t| t == self).unwrap_or(0) as u8
+    }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        Self::ALL.get(code.checked_sub(10)? as usize).copied()
+    }
+
+    /// 由 URL 參數解析
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.to_query() == s)
+    }
+
+    pub fn to_query(self) -> &'static str {
+        match self {
+            Self::Double => "x2",
+            Self::Bust => "bust",
+            Self::Redraw => "redraw",
+            Self::FreePick => "free",
+        }
+    }
+
+    /// 九宮格上顯示的文字
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Double => "×2",
+            Self::Bust => "爆",
+            Self::Redraw => "重抽",
+            Self::FreePick => "自選",
+        }
+    }
+
+    /// 網址中九宮格排列使用的字元
+    fn symbol(self) -> char {
+        match self {
+            Self::Double => 'd',
+            Self::Bust => 'b',
+            Self::Redraw => 'r',
+            Self::FreePick => 'f',
+        }
+    }
+}
+
+/// 九宮格一格的顯示文字：數字或特殊格名稱
+pub fn cell_label(code: u8) -> String {
+    match SpecialTile::from_code(code) {
+        Some(tile) => tile.label().to_string(),
+        None => code.to_string(),
+    }
+}
+
+/// 九宮格一格轉成網址中的字元
+pub fn cell_symbol(code: u8) -> char {
+    match SpecialTile::from_code(code) {
+        Some(tile) => tile.symbol(),
+        None => char::from(b'0' + code),
+    }
+}
+
+/// 由網址中的字元解析九宮格的一格
+pub fn parse_cell_symbol(c: char) -> Option<u8> {
+    match c.to_digit(10) {
+        Some(d) => Some(d as u8),
+        None => SpecialTile::ALL
+            .into_iter()
+            .find(|t| t.symbol() == c)
+            .map(SpecialTile::code),
+    }
+}
+
+/// 啟用的特殊格
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SpecialTiles(pub Vec<SpecialTile>);
+
+impl SpecialTiles {
+    /// 由 URL 參數（例如 "x2,bust"）解析
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut tiles = Self::default();
+        for name in s.split(',').filter(|n| !n.is_empty()) {
+            let tile = SpecialTile::parse(name)?;
+            if !tiles.contains(tile) {
+                tiles.toggle(tile);
+            }
+        }
+        Some(tiles)
+    }
+
+    pub fn to_query(&self) -> String {
+        self.0
+            .iter()
+            .map(|t| t.to_query())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    pub fn contains(&self, tile: SpecialTile) -> bool {
+        self.0.contains(&tile)
+    }
+
+    /// 切換某個特殊格是否啟用，維持 [`SpecialTile::ALL`] 的順序
+    pub fn toggle(&mut self, tile: SpecialTile) {
+        if self.contains(tile) {
+            self.0.retain(|&t| t != tile);
+        } else {
+            self.0.push(tile);
+            self.0.sort_by_key(|t| t.code());
+        }
+    }
+
+    /// 九宮格的初始排列：可用數字之後接著啟用的特殊格
+    pub fn pad_cells(&self, alphabet: &DigitAlphabet) -> Vec<u8> {
+        alphabet
+            .digits
+            .iter()
+            .copied()
+            .chain(self.0.iter().map(|t| t.code()))
+            .collect()
+    }
+}
+
+/// 一局遊戲的規則
+#[derive(Clone, PartialEq)]
+pub struct GameRules {
+    /// 需要選幾位數
+    pub digits_needed: u8,
+    /// 金額下限；`None` 表示不限
+    pub min_amount: Option<u64>,
+    /// 金額上限；`None` 表示不限
+    pub max_amount: Option<u64>,
+    /// 不允許重複
+    pub no_repeat: bool,
+    pub alphabet: DigitAlphabet,
+    pub fill_order: FillOrder,
+    /// 按到「爆」時的安慰獎
+    pub consolation: u64,
+    /// 不能收回已填入的數字（關閉悔棋或公正驗證）
+    pub no_takebacks: bool,
+}
+
+impl GameRules {
+    /// 位數模式；不允許重複時，位數不能超過可用數字的數量
+    pub fn by_digits(digits: u8, no_repeat: bool, alphabet: DigitAlphabet) -> Self {
+        let digits_needed = if no_repeat {
+            digits.min(alphabet.digits.len() as u8)
+        } else {
+            digits
+        };
+        Self {
+            digits_needed,
+            min_amount: None,
+            max_amount: None,
+            no_repeat,
+            alphabet,
+            fill_order: FillOrder::default(),
+            consolation: 0,
+            no_takebacks: false,
+        }
+    }
+
+    /// 最大金額模式
+    pub fn by_max_amount(max_amount: u64, no_repeat: bool, alphabet: DigitAlphabet) -> Self {
+        Self::with_bounds(None, max_amount, no_repeat, alphabet)
+    }
+
+    /// 金額範圍模式
+    pub fn by_range(
+        min_amount: u64,
+        max_amount: u64,
+        no_repeat: bool,
+        alphabet: DigitAlphabet,
+    ) -> Self {
+        Self::with_bounds(Some(min_amount), max_amount, no_repeat, alphabet)
+    }
+
+    /// 可完成的位數：最多 `max_amount` 的位數，若該位數無法組出範圍內的金額則減少一位
+    /// （例如上限 100 只能抽 2 位數）；任何位數都無法完成時維持上限的位數
+    fn with_bounds(
+        min_amount: Option<u64>,
+        max_amount: u64,
+        no_repeat: bool,
+        alphabet: DigitAlphabet,
+    ) -> Self {
+        let full = max_digits(max_amount);
+        let mut rules = Self {
+            digits_needed: full,
+            min_amount,
+            max_amount: Some(max_amount),
+            no_repeat,
+            alphabet,
+            fill_order: FillOrder::default(),
+            consolation: 0,
+            no_takebacks: false,
+        };
+        if let Some(n) = (1..=full).rev().find(|&n| {
+            rules.digits_needed = n;
+            rules.is_feasible()
+        }) {
+            rules.digits_needed = n;
+        } else {
+            rules.digits_needed = full;
+        }
+        rules
+    }
+
+    fn range(&self) -> (u64, u64) {
+        (
+            self.min_amount.unwrap_or(0),
+            self.max_amount.unwrap_or(u64::MAX),
+        )
+    }
+
+    /// 是否存在至少一種完成的選法
+    pub fn is_feasible(&self) -> bool {
+        self.can_complete(&vec![None; self.digits_needed as usize])
+    }
+
+    /// 已填入的位數（索引 0 為個位數）能否補滿其餘空位，且金額落在範圍內
+    fn can_complete(&self, slots: &[Option<u8>]) -> bool {
+        if slots.len() != self.digits_needed as usize {
+            return false;
+        }
+        let positions: Vec<usize> = (0..slots.len())
+            .rev()
+            .filter(|&pos| slots[pos].is_none())
+            .collect();
+        let mut used: Vec<u8> = slots.iter().flatten().copied().collect();
+        self.search(current_amount(slots), &positions, &mut used)
+    }
+
+    /// `digit` 能否放在第 `pos` 位
+    fn allows(&self, digit: u8, pos: usize, used: &[u8]) -> bool {
+        !(self.no_repeat && used.contains(&digit))
+            && self.alphabet.allows_at(digit, pos, self.digits_needed)
+    }
+
+    /// 剩餘位數（由高到低）以貪婪法補滿時的最小（或最大）金額；無法補滿時為 `None`
+    ///
+    /// 由高位往低位每次取允許的最小（最大）數字，即為所有補法中的最小（最大）值；
+    /// 較高的空位即使中間隔著已填入的位數，也比所有較低空位的總和大
+    fn greedy_completion(&self, positions: &[usize], used: &[u8], largest: bool) -> Option<u64> {
+        let mut used = used.to_vec();
+        let mut total = 0;
+        for &pos in positions {
+            let mut candidates = self
+                .alphabet
+                .digits
+                .iter()
+                .copied()
+                .filter(|&d| self.allows(d, pos, &used));
+            let digit = if largest {
+                candidates.next_back()
+            } else {
+                candidates.next()
+            }?;
+            used.push(digit);
+            total += digit as u64 * 10u64.pow(pos as u32);
+        }
+        Some(total)
+    }
+
+    /// 深度優先搜尋是否有補法讓金額落在範圍內；
+    /// 剩餘位數的最小、最大補法都在範圍內（或都在範圍外）時即可提早結束
+    fn search(&self, base: u64, positions: &[usize], used: &mut Vec<u8>) -> bool {
+        let (lo, hi) = self.range();
+        let (Some(min), Some(max)) = (
+            self.greedy_completion(positions, used, false),
+            self.greedy_completion(positions, used, true),
+        ) else {
+            return false;
+        };
+        if base + max < lo || base + min > hi {
+            return false;
+        }
+        if lo <= base + min && base + max <= hi {
+            return true;
+        }
+        let Some((&pos, rest)) = positions.split_first() else {
+            return false;
+        };
+        let candidates: Vec<u8> = self
+            .alphabet
+            .digits
+            .iter()
+            .copied()
+            .filter(|&d| self.allows(d, pos, used))
+            .collect();
+        candidates.into_iter().any(|d| {
+            used.push(d);
+            let found = self.search(base + d as u64 * 10u64.pow(pos as u32), rest, used);
+            used.pop();
+            found
+        })
+    }
+}
+
+/// 按下一個數字後發生的事
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    /// 數字填入第 `pos` 位（0 為個位數）
+    Picked { digit: u8, pos: usize },
+    /// 不允許重複時，點已選的數字取消選取；第 `pos` 位清空，其他位數不動
+    Removed { digit: u8, pos: usize },
+    /// 按下特殊格
+    Tile(SpecialTile),
+}
+
+/// 按下數字被拒絕的原因
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RejectReason {
+    /// 已開獎
+    Finished,
+    /// 位數已填滿
+    Full,
+    /// 自選位數時，還沒點選要填入的位數
+    NoSlot,
+    /// 不在可用數字中
+    NotInAlphabet,
+    /// 不允許重複時，數字已選過
+    Repeated,
+    /// 最高位不可為 0
+    LeadingZero,
+    /// 會超出金額範圍，或讓剩下的位數無法完成
+    ExceedsLimit,
+    /// 特殊格已經用過
+    TileUsed,
+    /// 不能收回已填入的數字
+    Locked,
+}
+
+/// 九宮格上單一數字的呈現狀態
+#[derive(Clone, Copy, Default, PartialEq)]
+pub struct CellState {
+    pub selected: bool,
+    pub disabled: bool,
+    /// 因金額範圍（或無法完成）而不可選
+    pub exceeds: bool,
+}
+
+/// 一局遊戲的狀態
+#[derive(Clone, PartialEq)]
+pub struct GameState {
+    rules: GameRules,
+    /// 各位數填入的數字，索引 0 為個位數
+    slots: Vec<Option<u8>>,
+    /// 依選取順序填入的位數
+    pick_order: Vec<usize>,
+    /// 自選位數（或「自選」特殊格）時，玩家點選的下一個位數
+    chosen_slot: Option<usize>,
+    /// 依按下順序，已用過的特殊格
+    tiles_used: Vec<SpecialTile>,
+    /// 按過「自選」，下一個數字還沒填入
+    free_pick: bool,
+    finished: bool,
+}
+
+impl GameState {
+    pub fn new(rules: GameRules) -> Self {
+        let slots = vec![None; rules.digits_needed as usize];
+        Self::with_slots(rules, slots)
+    }
+
+    /// 由既有的各位數狀態建立
+    pub fn with_slots(rules: GameRules, slots: Vec<Option<u8>>) -> Self {
+        let pick_order = (0..slots.len()).filter(|&i| slots[i].is_some()).collect();
+        Self {
+            rules,
+            slots,
+            pick_order,
+            chosen_slot: None,
+            tiles_used: Vec::new(),
+            free_pick: false,
+            finished: false,
+        }
+    }
+
+    /// 直接填滿固定的金額，例如紅包分配的最後一份
+    pub fn with_amount(rules: GameRules, amount: u64) -> Self {
+        let slots = (0..rules.digits_needed as u32)
+            .map(|i| Some((amount / 10u64.pow(i) % 10) as u8))
+            .collect();
+        Self::with_slots(rules, slots)
+    }
+
+    pub fn rules(&self) -> &GameRules {
+        &self.rules
+    }
+
+    /// 各位數填入的數字，索引 0 為個位數
+    pub fn slots(&self) -> &[Option<u8>] {
+        &self.slots
+    }
+
+    /// 依選取順序填入的位數
+    pub fn pick_order(&self) -> &[usize] {
+        &self.pick_order
+    }
+
+    /// 還沒有選取任何數字，也沒有按過特殊格
+    pub fn is_empty(&self) -> bool {
+        self.pick_order.is_empty() && self.tiles_used.is_empty()
+    }
+
+    /// 各位數組成的金額
+    pub fn amount(&self) -> u64 {
+        current_amount(&self.slots)
+    }
+
+    /// 依按下順序，已用過的特殊格
+    pub fn tiles_used(&self) -> &[SpecialTile] {
+        &self.tiles_used
+    }
+
+    /// 按到「爆」，這一局已結束
+    pub fn is_busted(&self) -> bool {
+        self.tiles_used.contains(&SpecialTile::Bust)
+    }
+
+    /// 開獎金額的倍數
+    pub fn multiplier(&self) -> u64 {
+        let doubles = self
+            .tiles_used
+            .iter()
+            .filter(|&&t| t == SpecialTile::Double)
+            .count();
+        2u64.saturating_pow(doubles as u32)
+    }
+
+    /// 下一個數字可以自己點選位數
+    pub fn is_free_pick(&self) -> bool {
+        self.free_pick && !self.finished
+    }
+
+    /// 開獎金額：爆掉時為安慰獎，否則為各位數組成的金額乘上倍數；都不超過金額上限
+    pub fn payout(&self) -> u64 {
+        let amount = if self.is_busted() {
+            self.rules.consolation
+        } else {
+            self.amount().saturating_mul(self.multiplier())
+        };
+        amount.min(self.rules.max_amount.unwrap_or(u64::MAX))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// 所有位數都已填滿
+    pub fn is_complete(&self) -> bool {
+        self.slots.iter().all(Option::is_some)
+    }
+
+    /// 下一個數字會填入的位數；隨機位數或自選位數尚未點選時為 `None`
+    pub fn next_slot(&self) -> Option<usize> {
+        match self.open_slots().as_slice() {
+            [pos] if !self.finished => Some(*pos),
+            _ => None,
+        }
+    }
+
+    /// 自選位數（或按過「自選」）時點選下一個要填入的位數；已填入的位數不能點選
+    pub fn choose_slot(&mut self, pos: usize) -> bool {
+        let open = !self.finished
+            && (self.rules.fill_order == FillOrder::PlayerChosen || self.free_pick)
+            && self.slots.get(pos) == Some(&None);
+        if open {
+            self.chosen_slot = Some(pos);
+        }
+        open
+    }
+
+    /// 按下 `digit`；隨機位數時由 `rng` 從可以填入的位數中挑一個。
+    /// `digit` 為特殊格的代碼時套用特殊格的效果
+    pub fn press(&mut self, digit: u8, rng: &mut impl Rng) -> Result<Event, RejectReason> {
+        if self.finished {
+            return Err(RejectReason::Finished);
+        }
+        if let Some(tile) = SpecialTile::from_code(digit) {
+            self.check_tile(tile)?;
+            self.tiles_used.push(tile);
+            match tile {
+                SpecialTile::Bust => self.finished = true,
+                SpecialTile::FreePick => self.free_pick = true,
+                SpecialTile::Double | SpecialTile::Redraw => {}
+            }
+            return Ok(Event::Tile(tile));
+        }
+        if self.rules.no_repeat {
+            if let Some(pos) = self.slots.iter().position(|&n| n == Some(digit)) {
+                if self.rules.no_takebacks {
+                    return Err(RejectReason::Locked);
+                }
+                self.slots[pos] = None;
+                self.pick_order.retain(|&p| p != pos);
+                return Ok(Event::Removed { digit, pos });
+            }
+        }
+        let candidates = self.check_pick(digit)?;
+        // 只有一個位數可填時不消耗亂數，維持相同種子下的盤面
+        let pos = match candidates.as_slice() {
+            [pos] => *pos,
+            _ => *candidates.choose(rng).ok_or(RejectReason::ExceedsLimit)?,
+        };
+        self.slots[pos] = Some(digit);
+        self.pick_order.push(pos);
+        self.chosen_slot = None;
+        self.free_pick = false;
+        Ok(Event::Picked { digit, pos })
+    }
+
+    /// 檢查特殊格能否按下：填滿後或已用過的不能再按
+    fn check_tile(&self, tile: SpecialTile) -> Result<(), RejectReason> {
+        if self.finished {
+            Err(RejectReason::Finished)
+        } else if self.is_complete() {
+            Err(RejectReason::Full)
+        } else if self.tiles_used.contains(&tile) {
+            Err(RejectReason::TileUsed)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 依填入順序，下一個數字可以放的位數
+    fn open_slots(&self) -> Vec<usize> {
+        let mut empty = (0..self.slots.len()).filter(|&i| self.slots[i].is_none());
+        match self.rules.fill_order {
+            FillOrder::PlayerChosen => self.chosen_slot.into_iter().collect(),
+            // 按過「自選」並點了位數時，只能填入該位數
+            _ if self.free_pick && self.chosen_slot.is_some() => {
+                self.chosen_slot.into_iter().collect()
+            }
+            FillOrder::OnesFirst => empty.next().into_iter().collect(),
+            FillOrder::HighFirst => empty.next_back().into_iter().collect(),
+            FillOrder::Random => empty.collect(),
+        }
+    }
+
+    /// 檢查 `digit` 能否填入第 `pos` 位
+    fn check_pick_at(&self, digit: u8, pos: usize) -> Result<(), RejectReason> {
+        let rules = &self.rules;
+        if !rules.alphabet.digits.contains(&digit) {
+            return Err(RejectReason::NotInAlphabet);
+        }
+        if rules.no_repeat && self.slots.contains(&Some(digit)) {
+            return Err(RejectReason::Repeated);
+        }
+        if !rules.alphabet.allows_at(digit, pos, rules.digits_needed) {
+            return Err(RejectReason::LeadingZero);
+        }
+        let mut next = self.slots.clone();
+        next[pos] = Some(digit);
+        if !rules.can_complete(&next) {
+            return Err(RejectReason::ExceedsLimit);
+        }
+        Ok(())
+    }
+
+    /// `digit` 可以填入的位數；都不能填入時回傳第一個位數被拒絕的原因
+    fn check_pick(&self, digit: u8) -> Result<Vec<usize>, RejectReason> {
+        if self.is_complete() {
+            return Err(RejectReason::Full);
+        }
+        let mut reason = None;
+        let mut candidates = Vec::new();
+        for pos in self.open_slots() {
+            match self.check_pick_at(digit, pos) {
+                Ok(()) => candidates.push(pos),
+                Err(e) => {
+                    reason.get_or_insert(e);
+                }
+            }
+        }
+        if candidates.is_empty() {
+            Err(reason.unwrap_or(RejectReason::NoSlot))
+        } else {
+            Ok(candidates)
+        }
+    }
+
+    /// `digit` 能否填入下一位（不含不允許重複時的取消選取）；特殊格為能否按下
+    pub fn can_pick(&self, digit: u8) -> bool {
+        match SpecialTile::from_code(digit) {
+            Some(tile) => self.check_tile(tile).is_ok(),
+            None => !self.finished && self.check_pick(digit).is_ok(),
+        }
+    }
+
+    /// `digit` 可以填入的位數（不含不允許重複時的取消選取）
+    pub fn candidate_slots(&self, digit: u8) -> Vec<usize> {
+        if self.finished {
+            return Vec::new();
+        }
+        self.check_pick(digit).unwrap_or_default()
+    }
+
+    /// `digit` 在九宮格上的呈現狀態；特殊格用過後視為已選
+    pub fn cell_state(&self, digit: u8) -> CellState {
+        if let Some(tile) = SpecialTile::from_code(digit) {
+            return CellState {
+                selected: self.tiles_used.contains(&tile),
+                disabled: self.check_tile(tile).is_err(),
+                exceeds: false,
+            };
+        }
+        let selected = self.slots.contains(&Some(digit));
+        // 不允許重複時，已選的數字可以點擊取消；不能收回時維持鎖定
+        let removable = self.rules.no_repeat && !self.rules.no_takebacks && selected;
+        let check = self.check_pick(digit);
+        let exceeds = !removable
+            && matches!(
+                check,
+                Err(RejectReason::LeadingZero | RejectReason::ExceedsLimit)
+            );
+        let disabled = self.finished || (!removable && check.is_err());
+        CellState {
+            selected,
+            disabled,
+            exceeds,
+        }
+    }
+
+    /// 開獎；需先填滿所有位數（按到「爆」時已經結束）
+    pub fn finish(&mut self) -> bool {
+        if self.is_complete() {
+            self.finished = true;
+        }
+        self.finished
+    }
+
+    /// 清空選取，重新開始
+    pub fn reset(&mut self) {
+        self.slots.iter_mut().for_each(|s| *s = None);
+        self.pick_order.clear();
+        self.chosen_slot = None;
+        self.tiles_used.clear();
+        self.free_pick = false;
+        self.finished = false;
+    }
+}
+
+/// 隨機隱藏位數使用的 ChaCha 亂數串流；九宮格使用預設的串流 0
+pub const HIDDEN_STREAM: u64 = 1;
+
+/// 隱藏模式：開獎前哪些位數以 `$` 顯示
+#[derive(Clone, Default, PartialEq)]
+pub enum HiddenPolicy {
+    /// 不隱藏
+    #[default]
+    Off,
+    /// 只隱藏最高位
+    Highest,
+    /// 隱藏所有位數
+    All,
+    /// 隱藏指定的位數（0 為個位數）
+    Positions(Vec<usize>),
+    /// 每局隨機隱藏幾個位數
+    Random(u8),
+}
+
+impl HiddenPolicy {
+    /// 由 URL 參數解析：`1`、`all`、`pos:0,2`、`random:2`
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "0" => return Some(Self::Off),
+            "1" => return Some(Self::Highest),
+            "all" => return Some(Self::All),
+            _ => {}
+        }
+        if let Some(list) = s.strip_prefix("pos:") {
+            let mut positions: Vec<usize> = list
+                .split(',')
+                .filter(|p| !p.is_empty())
+                .map(|p| p.parse().ok().filter(|&p| p < 9))
+                .collect::<Option<_>>()?;
+            positions.sort_unstable();
+            positions.dedup();
+            return Some(Self::Positions(positions));
+        }
+        s.strip_prefix("random:")?
+            .parse()
+            .ok()
+            .filter(|n| (1..=9).contains(n))
+            .map(Self::Random)
+    }
+
+    /// 轉成 URL 參數字串
+    pub fn to_query(&self) -> String {
+        match self {
+            Self::Off => "0".to_string(),
+            Self::Highest => "1".to_string(),
+            Self::All => "all".to_string(),
+            Self::Positions(positions) => {
+                let list: Vec<String> = positions.iter().map(|p| p.to_string()).collect();
+                format!("pos:{}", list.join(","))
+            }
+            Self::Random(n) => format!("random:{}", n),
+        }
+    }
+
+    /// 由種子建立決定隱藏位數的亂數產生器；使用另一條串流，不影響九宮格的盤面
+    pub fn rng(seed: u64) -> ChaCha8Rng {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        rng.set_stream(HIDDEN_STREAM);
+        rng
+    }
+
+    /// 本局要隱藏的位數（由小到大）；隨機隱藏時由 `rng` 決定
+    pub fn hidden_slots(&self, digits_needed: u8, rng: &mut impl Rng) -> Vec<usize> {
+        let n = digits_needed as usize;
+        match self {
+            Self::Off => Vec::new(),
+            Self::Highest => (n > 0).then(|| n - 1).into_iter().collect(),
+            Self::All => (0..n).collect(),
+            Self::Positions(positions) => positions.iter().copied().filter(|&p| p < n).collect(),
+            Self::Random(count) => {
+                let mut slots = index::sample(rng, n, (*count as usize).min(n)).into_vec();
+                slots.sort_unstable();
+                slots
+            }
+        }
+    }
+}
+
+/// 各數字被抽中的權重（索引為數字 0-9）；權重 0 表示不會被抽中
+#[derive(Clone, Copy, PartialEq)]
+pub struct DigitWeights(pub [u8; 10]);
+
+impl Default for DigitWeights {
+    fn default() -> Self {
+        Self([1; 10])
+    }
+}
+
+impl DigitWeights {
+    /// 由 URL 參數（10 個 0-9 的字元，依序為數字 0-9 的權重）解析
+    pub fn parse(s: &str) -> Option<Self> {
+        let weights: Vec<u8> = s
+            .chars()
+            .map(|c| c.to_digit(10).map(|d| d as u8))
+            .collect::<Option<_>>()?;
+        weights.try_into().ok().map(Self)
+    }
+
+    /// 轉成 URL 參數字串
+    pub fn to_query(self) -> String {
+        self.0.iter().map(|w| w.to_string()).collect()
+    }
+
+    /// 候選數字各自被抽中的機率；權重全為 0 時視為平均
+    pub fn probabilities(&self, candidates: &[u8]) -> Vec<(u8, f64)> {
+        let total: u32 = candidates.iter().map(|&d| self.0[d as usize] as u32).sum();
+        candidates
+            .iter()
+            .map(|&d| {
+                let p = if total == 0 {
+                    1.0 / candidates.len() as f64
+                } else {
+                    self.0[d as usize] as f64 / total as f64
+                };
+                (d, p)
+            })
+            .collect()
+    }
+
+    /// 依權重從候選數字中抽一個
+    pub fn pick(&self, candidates: &[u8], rng: &mut impl Rng) -> Option<u8> {
+        let total: u32 = candidates.iter().map(|&d| self.0[d as usize] as u32).sum();
+        if total == 0 {
+            return candidates.choose(rng).copied();
+        }
+        let mut r = rng.gen_range(0..total);
+        for &d in candidates {
+            let w = self.0[d as usize] as u32;
+            if r < w {
+                return Some(d);
+            }
+            r -= w;
+        }
+        None
+    }
+}
+
+/// 精確列舉時的位數上限，超過時狀態數過多
+pub const MAX_ENUM_DIGITS: u8 = 5;
+
+/// 每次都依權重從可選的數字中抽一個時，最終金額的機率分佈（由小到大）；
+/// 隨機位數時，每個可填入的位數機率相同。
+/// 位數超過 [`MAX_ENUM_DIGITS`]、規則無法完成或由玩家自選位數時為 `None`
+pub fn amount_distribution(rules: &GameRules, weights: &DigitWeights) -> Option<Vec<(u64, f64)>> {
+    if rules.digits_needed > MAX_ENUM_DIGITS
+        || rules.fill_order == FillOrder::PlayerChosen
+        || !rules.is_feasible()
+    {
+        return None;
+    }
+    // 各位數狀態相同的局面合併，逐次展開
+    let empty = vec![None; rules.digits_needed as usize];
+    let mut states: HashMap<Vec<Option<u8>>, f64> = HashMap::from([(empty, 1.0)]);
+    for _ in 0..rules.digits_needed {
+        let mut next: HashMap<Vec<Option<u8>>, f64> = HashMap::new();
+        for (slots, p) in states {
+            let state = GameState::with_slots(rules.clone(), slots);
+            let candidates: Vec<u8> = rules
+                .alphabet
+                .digits
+                .iter()
+                .copied()
+                .filter(|&d| state.can_pick(d))
+                .collect();
+            for (d, q) in weights.probabilities(&candidates) {
+                let positions = state.candidate_slots(d);
+                let share = p * q / positions.len() as f64;
+                for pos in positions {
+                    let mut picked = state.slots().to_vec();
+                    picked[pos] = Some(d);
+                    *next.entry(picked).or_default() += share;
+                }
+            }
+        }
+        states = next;
+    }
+    let mut dist: Vec<(u64, f64)> = states
+        .into_iter()
+        .map(|(slots, p)| (current_amount(&slots), p))
+        .collect();
+    dist.sort_by_key(|&(amount, _)| amount);
+    Some(dist)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn alphabet_0_9(leading_zero: bool) -> DigitAlphabet {
+        DigitAlphabet::parse("0123456789", leading_zero).unwrap()
+    }
+
+    /// 每次從可選的數字中隨機按一個，直到填滿；途中沒有可選的數字時為 `None`
+    fn play_randomly(rules: &GameRules, rng: &mut ChaCha8Rng) -> Option<GameState> {
+        let mut state = GameState::new(rules.clone());
+        while !state.is_complete() {
+            let candidates: Vec<u8> = rules
+                .alphabet
+                .digits
+                .iter()
+                .copied()
+                .filter(|&d| state.can_pick(d))
+                .collect();
+            let digit = *candidates.choose(rng)?;
+            state.press(digit, rng).ok()?;
+        }
+        Some(state)
+    }
+
+    #[test]
+    fn max_amount_never_dead_ends() {
+        for no_repeat in [false, true] {
+            let rules = GameRules::by_max_amount(150, no_repeat, DigitAlphabet::default());
+            assert_eq!(rules.digits_needed, 3);
+            for seed in 0..200 {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                let state = play_randomly(&rules, &mut rng).expect("不應卡住");
+                assert!(state.amount() <= 150);
+            }
+        }
+    }
+
+    #[test]
+    fn max_amount_drops_a_digit_when_full_width_is_impossible() {
+        // 1-9 組不出 100，只能抽 2 位數
+        let rules = GameRules::by_max_amount(100, false, DigitAlphabet::default());
+        assert_eq!(rules.digits_needed, 2);
+        assert!(rules.is_feasible());
+    }
+
+    #[test]
+    fn range_rules_stay_within_bounds() {
+        let rules = GameRules::by_range(300, 420, true, DigitAlphabet::default());
+        for seed in 0..100 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let state = play_randomly(&rules, &mut rng).expect("不應卡住");
+            assert!((300..=420).contains(&state.amount()));
+        }
+    }
+
+    #[test]
+    fn leading_zero_rules() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut rules = GameRules::by_digits(3, false, alphabet_0_9(false));
+        rules.fill_order = FillOrder::HighFirst;
+        let mut state = GameState::new(rules.clone());
+        assert_eq!(state.press(0, &mut rng), Err(RejectReason::LeadingZero));
+        assert!(state.cell_state(0).exceeds);
+        assert_eq!(
+            state.press(1, &mut rng),
+            Ok(Event::Picked { digit: 1, pos: 2 })
+        );
+        // 最高位以外可以是 0
+        assert_eq!(
+            state.press(0, &mut rng),
+            Ok(Event::Picked { digit: 0, pos: 1 })
+        );
+
+        rules.alphabet.leading_zero = true;
+        let mut state = GameState::new(rules);
+        assert_eq!(
+            state.press(0, &mut rng),
+            Ok(Event::Picked { digit: 0, pos: 2 })
+        );
+
+        // 只有 1 位數時，那一位就是最高位
+        let one = alphabet_0_9(false);
+        assert!(!one.allows_at(0, 0, 1));
+        assert!(one.allows_at(0, 0, 2));
+    }
+
+    #[test]
+    fn fill_order_slot_selection() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut rules = GameRules::by_digits(3, false, DigitAlphabet::default());
+
+        let mut state = GameState::new(rules.clone());
+        for expected in 0..3 {
+            assert_eq!(
+                state.press(5, &mut rng),
+                Ok(Event::Picked {
+                    digit: 5,
+                    pos: expected
+                })
+            );
+        }
+
+        rules.fill_order = FillOrder::HighFirst;
+        let mut state = GameState::new(rules.clone());
+        for expected in (0..3).rev() {
+            assert_eq!(state.next_slot(), Some(expected));
+            state.press(5, &mut rng).unwrap();
+        }
+        assert_eq!(state.pick_order(), [2, 1, 0]);
+
+        rules.fill_order = FillOrder::Random;
+        let mut state = GameState::new(rules.clone());
+        assert_eq!(state.next_slot(), None);
+        for _ in 0..3 {
+            state.press(5, &mut rng).unwrap();
+        }
+        let mut order = state.pick_order().to_vec();
+        order.sort_unstable();
+        assert_eq!(order, [0, 1, 2]);
+
+        rules.fill_order = FillOrder::PlayerChosen;
+        let mut state = GameState::new(rules);
+        assert_eq!(state.press(5, &mut rng), Err(RejectReason::NoSlot));
+        assert!(state.choose_slot(1));
+        assert_eq!(
+            state.press(5, &mut rng),
+            Ok(Event::Picked { digit: 5, pos: 1 })
+        );
+        // 已填入的位數不能再點選
+        assert!(!state.choose_slot(1));
+        assert_eq!(state.press(6, &mut rng), Err(RejectReason::NoSlot));
+    }
+
+    #[test]
+    fn no_repeat_toggles_off_a_picked_digit() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let rules = GameRules::by_digits(3, true, DigitAlphabet::default());
+        let mut state = GameState::new(rules);
+        state.press(3, &mut rng).unwrap();
+        state.press(4, &mut rng).unwrap();
+        assert!(state.cell_state(3).selected);
+        assert!(!state.cell_state(3).disabled);
+        assert!(!state.can_pick(3));
+        assert_eq!(
+            state.press(3, &mut rng),
+            Ok(Event::Removed { digit: 3, pos: 0 })
+        );
+        assert_eq!(state.slots(), [None, Some(4), None]);
+        assert_eq!(state.pick_order(), [1]);
+        // 空出的個位數再填回去
+        assert_eq!(
+            state.press(7, &mut rng),
+            Ok(Event::Picked { digit: 7, pos: 0 })
+        );
+    }
+
+    #[test]
+    fn no_takebacks_locks_picked_digits() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut rules = GameRules::by_digits(3, true, DigitAlphabet::default());
+        rules.no_takebacks = true;
+        let mut state = GameState::new(rules);
+        state.press(3, &mut rng).unwrap();
+        let three = state.cell_state(3);
+        assert!(three.selected && three.disabled && !three.exceeds);
+        assert_eq!(state.press(3, &mut rng), Err(RejectReason::Locked));
+        assert_eq!(state.slots(), [Some(3), None, None]);
+        assert_eq!(state.pick_order(), [0]);
+    }
+
+    #[test]
+    fn cell_state_exceeds_and_disabled() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut rules = GameRules::by_max_amount(150, false, DigitAlphabet::default());
+        rules.fill_order = FillOrder::HighFirst;
+        let mut state = GameState::new(rules);
+        let two = state.cell_state(2);
+        assert!(two.exceeds && two.disabled && !two.selected);
+        assert!(!state.cell_state(1).disabled);
+
+        state.press(1, &mut rng).unwrap();
+        // 1_ _：個位數至少 1，十位數最多 4
+        assert!(!state.cell_state(4).disabled);
+        assert!(state.cell_state(5).exceeds);
+        assert_eq!(state.press(5, &mut rng), Err(RejectReason::ExceedsLimit));
+        state.press(4, &mut rng).unwrap();
+        state.press(9, &mut rng).unwrap();
+        assert_eq!(state.amount(), 149);
+        // 填滿後都不可選，但已選的數字維持選取
+        let one = state.cell_state(1);
+        assert!(one.selected && one.disabled && !one.exceeds);
+        assert!(state.finish());
+        assert_eq!(state.press(1, &mut rng), Err(RejectReason::Finished));
+    }
+
+    #[test]
+    fn reset_clears_the_round() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let rules = GameRules::by_digits(2, false, DigitAlphabet::default());
+        let mut state = GameState::new(rules);
+        state.press(SpecialTile::Double.code(), &mut rng).unwrap();
+        state.press(4, &mut rng).unwrap();
+        state.press(2, &mut rng).unwrap();
+        assert!(state.finish());
+        state.reset();
+        assert!(state.is_empty());
+        assert!(!state.is_finished());
+        assert_eq!(state.slots(), [None, None]);
+        assert_eq!(state.multiplier(), 1);
+        assert!(state.can_pick(SpecialTile::Double.code()));
+    }
+
+    #[test]
+    fn special_tiles() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut rules = GameRules::by_max_amount(500, false, DigitAlphabet::default());
+        rules.consolation = 20;
+        let mut state = GameState::new(rules.clone());
+        let double = SpecialTile::Double.code();
+        assert_eq!(
+            state.press(double, &mut rng),
+            Ok(Event::Tile(SpecialTile::Double))
+        );
+        assert_eq!(state.press(double, &mut rng), Err(RejectReason::TileUsed));
+        for d in [9, 9, 3] {
+            state.press(d, &mut rng).unwrap();
+        }
+        assert_eq!(state.amount(), 399);
+        // 加倍不超過金額上限
+        assert_eq!(state.payout(), 500);
+
+        let mut state = GameState::new(rules.clone());
+        state.press(SpecialTile::Bust.code(), &mut rng).unwrap();
+        assert!(state.is_finished() && state.is_busted());
+        assert_eq!(state.payout(), 20);
+
+        let mut state = GameState::new(rules);
+        state.press(SpecialTile::FreePick.code(), &mut rng).unwrap();
+        assert!(state.is_free_pick());
+        assert!(state.choose_slot(2));
+        assert_eq!(
+            state.press(2, &mut rng),
+            Ok(Event::Picked { digit: 2, pos: 2 })
+        );
+        assert!(!state.is_free_pick());
+        assert!(!state.choose_slot(1));
+    }
+
+    #[test]
+    fn query_round_trips() {
+        let specials = SpecialTiles::parse("redraw,x2,x2").unwrap();
+        assert_eq!(specials.to_query(), "x2,redraw");
+        assert!(SpecialTiles::parse("x3").is_none());
+        for code in 0..=13 {
+            assert_eq!(parse_cell_symbol(cell_symbol(code)), Some(code));
+        }
+        for s in ["1", "all", "pos:0,2", "random:2"] {
+            assert_eq!(HiddenPolicy::parse(s).unwrap().to_query(), s);
+        }
+        for order in FillOrder::ALL {
+            assert_eq!(FillOrder::parse(order.to_query()), Some(order));
+        }
+        assert_eq!(DigitWeights::parse("1111111113").unwrap().0[9], 3);
+        assert!(DigitWeights::parse("111").is_none());
+    }
+
+    #[test]
+    fn distribution_sums_to_one() {
+        let rules = GameRules::by_max_amount(150, true, DigitAlphabet::default());
+        let dist = amount_distribution(&rules, &DigitWeights::default()).unwrap();
+        let total: f64 = dist.iter().map(|&(_, p)| p).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert!(dist.iter().all(|&(amount, _)| amount <= 150));
+    }
+}
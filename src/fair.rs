@@ -0,0 +1,451 @@
+//! 公正驗證：每局開始前公開種子的 SHA-256 承諾值，開獎後公開種子，
+//! 任何人都能由種子與操作紀錄重播出每一次的九宮格排列
+
+use std::fmt::Write;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::engine::{
+    DigitAlphabet, DigitWeights, Event, FillOrder, GameRules, GameState, SpecialTile, SpecialTiles,
+};
+use crate::pad::{self, PadLayout, SpinStyle};
+
+/// 會用到亂數的九宮格操作，依發生順序記錄
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PadAction {
+    /// 隨機位置（含翻牌模式每局開始時的洗牌）
+    Shuffle,
+    /// 隨機轉動
+    Spin(SpinStyle),
+    /// 自動抽選挑格子；接著一定是一筆 `Press`
+    AutoPick,
+    /// 點了顯示 `digit` 的格子；特殊格為 [`SpecialTile::code`]
+    Press(u8),
+    /// 改變九宮格的列數與欄數；開局時為預設排法
+    Layout(PadLayout),
+}
+
+impl PadAction {
+    fn code(self) -> String {
+        match self {
+            Self::Shuffle => "S".to_string(),
+            Self::Spin(SpinStyle::Classic) => "T".to_string(),
+            Self::Spin(style) => format!("T:{}", style.to_query()),
+            Self::AutoPick => "A".to_string(),
+            Self::Press(d) => format!("P{}", d),
+            Self::Layout(layout) => format!("L{}", layout.to_query()),
+        }
+    }
+
+    fn parse(code: &str) -> Option<Self> {
+        match code {
+            "S" => Some(Self::Shuffle),
+            "T" => Some(Self::Spin(SpinStyle::Classic)),
+            "A" => Some(Self::AutoPick),
+            _ => {
+                if let Some(style) = code.strip_prefix("T:") {
+                    return SpinStyle::parse(style).map(Self::Spin);
+                }
+                if let Some(layout) = code.strip_prefix('L') {
+                    return PadLayout::parse(layout).map(Self::Layout);
+                }
+                code.strip_prefix('P')?
+                    .parse()
+                    .ok()
+                    .filter(|&d| d <= 9 || SpecialTile::from_code(d).is_some())
+                    .map(Self::Press)
+            }
+        }
+    }
+}
+
+/// 操作紀錄轉成文字，例如 "S,P3,T,A,P7,T:ring"
+pub fn actions_to_string(actions: &[PadAction]) -> String {
+    actions
+        .iter()
+        .map(|a| a.code())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn parse_actions(s: &str) -> Option<Vec<PadAction>> {
+    s.split(',')
+        .filter(|c| !c.is_empty())
+        .map(PadAction::parse)
+        .collect()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{:02x}", b);
+        hex
+    })
+}
+
+/// 承諾值：`SHA-256("<種子>:<nonce>")` 的十六進位字串
+pub fn commitment(seed: u64, nonce: &str) -> String {
+    to_hex(&Sha256::digest(format!("{}:{}", seed, nonce)))
+}
+
+/// 開局時會影響重播的選項；驗證資料依開局時的值產生
+#[derive(Clone, Default, PartialEq)]
+pub struct FairOptions {
+    pub weights: DigitWeights,
+    /// 選取時看不到數字（隱藏模式或翻牌模式）
+    pub blind: bool,
+    pub face_down: bool,
+    pub specials: SpecialTiles,
+}
+
+/// 進行中的一局：種子在開獎前不公開
+#[derive(Clone, PartialEq)]
+pub struct FairRound {
+    pub seed: u64,
+    /// 隨機 128 位元，避免從承諾值暴力反推種子
+    pub nonce: String,
+    pub commitment: String,
+    pub actions: Vec<PadAction>,
+    pub options: FairOptions,
+}
+
+impl FairRound {
+    pub fn generate(rng: &mut impl Rng) -> Self {
+        let seed = rng.gen();
+        let nonce = to_hex(&rng.gen::<[u8; 16]>());
+        Self {
+            commitment: commitment(seed, &nonce),
+            seed,
+            nonce,
+            actions: Vec::new(),
+            options: FairOptions::default(),
+        }
+    }
+
+    /// 開獎後公開的驗證資料
+    pub fn proof(&self, state: &GameState) -> FairProof {
+        let rules = state.rules();
+        FairProof {
+            commitment: self.commitment.clone(),
+            seed: self.seed,
+            nonce: self.nonce.clone(),
+            digits_needed: rules.digits_needed,
+            min_amount: rules.min_amount,
+            max_amount: rules.max_amount,
+            no_repeat: rules.no_repeat,
+            alphabet: rules.alphabet.to_query(),
+            leading_zero: rules.alphabet.leading_zero,
+            weights: self.options.weights.to_query(),
+            fill_order: rules.fill_order.to_query().to_string(),
+            blind: self.options.blind,
+            face_down: self.options.face_down,
+            specials: self.options.specials.to_query(),
+            consolation: rules.consolation,
+            actions: actions_to_string(&self.actions),
+            amount: state.payout(),
+        }
+    }
+}
+
+/// 開獎後公開的驗證資料
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct FairProof {
+    pub commitment: String,
+    pub seed: u64,
+    pub nonce: String,
+    pub digits_needed: u8,
+    pub min_amount: Option<u64>,
+    pub max_amount: Option<u64>,
+    pub no_repeat: bool,
+    pub alphabet: String,
+    pub leading_zero: bool,
+    pub weights: String,
+    pub fill_order: String,
+    /// 選取時看不到數字（隱藏模式或翻牌模式），權重會影響翻開的數字
+    pub blind: bool,
+    pub face_down: bool,
+    /// 混在九宮格中的特殊格，見 [`SpecialTiles::to_query`]
+    #[serde(default)]
+    pub specials: String,
+    #[serde(default)]
+    pub consolation: u64,
+    /// 操作紀錄，見 [`actions_to_string`]
+    pub actions: String,
+    pub amount: u64,
+}
+
+/// 驗證失敗的原因
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FairError {
+    /// 格式錯誤
+    Invalid,
+    /// 種子與 nonce 不符合承諾值
+    Commitment,
+    /// 重播出的金額與公布的不同
+    Amount,
+}
+
+impl FairError {
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::Invalid => "驗證資料格式錯誤",
+            Self::Commitment => "種子與承諾值不符",
+            Self::Amount => "重播結果與公布的金額不同",
+        }
+    }
+}
+
+/// 重播的結果
+pub struct Replay {
+    /// 每次操作後的九宮格排列、transform 與列數欄數，第一筆為開局時
+    pub layouts: Vec<(Vec<u8>, usize, PadLayout)>,
+    pub amount: u64,
+}
+
+/// 由金額設定推出的位數；與驗證資料不符（或超出 1-9 位）時為 `None`，
+/// 避免竄改的位數讓金額計算溢位
+fn checked_digits(proof: &FairProof, alphabet: &DigitAlphabet) -> Option<u8> {
+    let alphabet = alphabet.clone();
+    let expected = match (proof.min_amount, proof.max_amount) {
+        (Some(min), Some(max)) => GameRules::by_range(min, max, proof.no_repeat, alphabet),
+        (None, Some(max)) => GameRules::by_max_amount(max, proof.no_repeat, alphabet),
+        (None, None) => GameRules::by_digits(proof.digits_needed, proof.no_repeat, alphabet),
+        (Some(_), None) => return None,
+    };
+    Some(expected.digits_needed).filter(|&n| n == proof.digits_needed && (1..=9).contains(&n))
+}
+
+/// 由開局狀態（九宮格依可用數字與特殊格排列、沒有 transform）重播操作紀錄
+fn replay(proof: &FairProof) -> Option<Replay> {
+    let alphabet = DigitAlphabet::parse(&proof.alphabet, proof.leading_zero)?;
+    let rules = GameRules {
+        digits_needed: checked_digits(proof, &alphabet)?,
+        min_amount: proof.min_amount,
+        max_amount: proof.max_amount,
+        no_repeat: proof.no_repeat,
+        alphabet,
+        fill_order: FillOrder::parse(&proof.fill_order)?,
+        consolation: proof.consolation,
+        // 公正驗證一律不能收回
+        no_takebacks: true,
+    };
+    let specials = SpecialTiles::parse(&proof.specials)?;
+    let weights = DigitWeights::parse(&proof.weights)?;
+    let actions = parse_actions(&proof.actions)?;
+
+    let mut rng = ChaCha8Rng::seed_from_u64(proof.seed);
+    let mut grid = specials.pad_cells(&rules.alphabet);
+    let mut transform = 0;
+    let mut layout = PadLayout::auto(grid.len());
+    let mut state = GameState::new(rules);
+    let mut layouts = vec![(grid.clone(), transform, layout)];
+    for action in actions {
+        match action {
+            PadAction::Shuffle => pad::shuffle(&mut grid, &mut rng),
+            PadAction::Spin(style) => {
+                match pad::spin(transform, style, layout, grid.len(), &mut rng) {
+                    pad::Spin::Transform(next) => transform = next,
+                    pad::Spin::Rotate(dir) => pad::rotate_lines(&mut grid, layout, dir),
+                    pad::Spin::Permute(perm) => pad::permute(&mut grid, layout, perm),
+                }
+            }
+            PadAction::Layout(next) if next.fits(grid.len()) => layout = next,
+            PadAction::Layout(_) => return None,
+            // 沒有啟用的特殊格不可能被按到
+            PadAction::Press(code)
+                if SpecialTile::from_code(code).is_some() && !grid.contains(&code) =>
+            {
+                return None
+            }
+            PadAction::AutoPick => {
+                pad::auto_choice(&state, &grid, &mut rng);
+            }
+            PadAction::Press(digit) => {
+                let num = if proof.blind {
+                    pad::weighted_remap(&state, &mut grid, digit, &weights, &mut rng)
+                } else {
+                    digit
+                };
+                match state.press(num, &mut rng) {
+                    Ok(Event::Tile(SpecialTile::Redraw)) => pad::shuffle(&mut grid, &mut rng),
+                    Ok(_) if proof.face_down => pad::shuffle(&mut grid, &mut rng),
+                    _ => {}
+                }
+            }
+        }
+        layouts.push((grid.clone(), transform, layout));
+    }
+    Some(Replay {
+        layouts,
+        amount: state.payout(),
+    })
+}
+
+/// 驗證承諾值並重播；重播出的金額必須與公布的相同
+pub fn verify(proof: &FairProof) -> Result<Replay, FairError> {
+    if commitment(proof.seed, &proof.nonce) != proof.commitment {
+        return Err(FairError::Commitment);
+    }
+    let replay = replay(proof).ok_or(FairError::Invalid)?;
+    if replay.amount != proof.amount {
+        return Err(FairError::Amount);
+    }
+    Ok(replay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn actions_round_trip() {
+        let actions = vec![
+            PadAction::Shuffle,
+            PadAction::Press(3),
+            PadAction::Spin(SpinStyle::Classic),
+            PadAction::AutoPick,
+            PadAction::Press(SpecialTile::Redraw.code()),
+            PadAction::Spin(SpinStyle::Ring),
+            PadAction::Layout(PadLayout { rows: 2, cols: 5 }),
+        ];
+        let text = actions_to_string(&actions);
+        assert_eq!(text, "S,P3,T,A,P12,T:ring,L2x5");
+        assert_eq!(parse_actions(&text), Some(actions));
+        assert_eq!(parse_actions("P14"), None);
+        assert_eq!(parse_actions("T:spiral"), None);
+        assert_eq!(parse_actions(""), Some(Vec::new()));
+    }
+
+    /// 依畫面上的步驟操作一局並記錄，回傳開獎後的驗證資料
+    fn play_round(seed: u64, face_down: bool) -> FairProof {
+        let rules = GameRules::by_max_amount(5000, true, DigitAlphabet::default());
+        let specials = SpecialTiles(vec![SpecialTile::Double, SpecialTile::Redraw]);
+        let layout = PadLayout { rows: 4, cols: 3 };
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut grid = specials.pad_cells(&rules.alphabet);
+        let mut transform = 0;
+        let mut state = GameState::new(rules.clone());
+        let mut actions = vec![PadAction::Layout(layout)];
+        if face_down {
+            actions.push(PadAction::Shuffle);
+            pad::shuffle(&mut grid, &mut rng);
+        }
+        let mut step = 0;
+        while !state.is_complete() {
+            // 輪流轉動與按下第一個可選的格子
+            if step % 2 == 0 {
+                let style = SpinStyle::ALL[step / 2 % SpinStyle::ALL.len()];
+                actions.push(PadAction::Spin(style));
+                match pad::spin(transform, style, layout, grid.len(), &mut rng) {
+                    pad::Spin::Transform(next) => transform = next,
+                    pad::Spin::Rotate(dir) => pad::rotate_lines(&mut grid, layout, dir),
+                    pad::Spin::Permute(perm) => pad::permute(&mut grid, layout, perm),
+                }
+            } else {
+                let cell = *grid.iter().find(|&&d| state.can_pick(d)).unwrap();
+                actions.push(PadAction::Press(cell));
+                match state.press(cell, &mut rng) {
+                    Ok(Event::Tile(SpecialTile::Redraw)) => pad::shuffle(&mut grid, &mut rng),
+                    Ok(_) if face_down => pad::shuffle(&mut grid, &mut rng),
+                    _ => {}
+                }
+            }
+            step += 1;
+        }
+        assert!(state.finish());
+        let nonce = "0123456789abcdef".to_string();
+        let round = FairRound {
+            commitment: commitment(seed, &nonce),
+            seed,
+            nonce,
+            actions,
+            options: FairOptions {
+                weights: DigitWeights::default(),
+                blind: face_down,
+                face_down,
+                specials,
+            },
+        };
+        round.proof(&state)
+    }
+
+    #[test]
+    fn recorded_rounds_verify() {
+        for seed in 0..20 {
+            for face_down in [false, true] {
+                let proof = play_round(seed, face_down);
+                let replay = verify(&proof).unwrap_or_else(|e| panic!("{}", e.message()));
+                assert_eq!(replay.amount, proof.amount);
+                assert_eq!(
+                    replay.layouts.len(),
+                    parse_actions(&proof.actions).unwrap().len() + 1
+                );
+                let (_, _, last) = replay.layouts.last().unwrap();
+                assert_eq!(*last, PadLayout { rows: 4, cols: 3 });
+            }
+        }
+    }
+
+    #[test]
+    fn tampered_proofs_fail() {
+        let proof = play_round(1, false);
+        let wrong_amount = FairProof {
+            amount: proof.amount + 1,
+            ..proof.clone()
+        };
+        assert!(matches!(verify(&wrong_amount), Err(FairError::Amount)));
+        let wrong_seed = FairProof {
+            seed: proof.seed + 1,
+            ..proof.clone()
+        };
+        assert!(matches!(verify(&wrong_seed), Err(FairError::Commitment)));
+        let garbled = FairProof {
+            actions: "P3,X".to_string(),
+            ..proof
+        };
+        assert!(matches!(verify(&garbled), Err(FairError::Invalid)));
+    }
+
+    #[test]
+    fn options_toggled_mid_round_do_not_verify() {
+        // 開局後才調整權重，以新的選項產生的驗證資料重播不出相同的金額
+        for seed in 0..20 {
+            let proof = play_round(seed, true);
+            let toggled = FairProof {
+                weights: "0000000009".to_string(),
+                ..proof
+            };
+            assert!(verify(&toggled).is_err(), "{}", seed);
+        }
+    }
+
+    #[test]
+    fn impossible_digit_counts_are_invalid() {
+        let proof = play_round(2, false);
+        for digits_needed in [0, 3, 20, 255] {
+            let tampered = FairProof {
+                digits_needed,
+                ..proof.clone()
+            };
+            assert!(matches!(verify(&tampered), Err(FairError::Invalid)));
+        }
+        let by_digits = FairProof {
+            digits_needed: 20,
+            min_amount: None,
+            max_amount: None,
+            ..proof
+        };
+        assert!(matches!(verify(&by_digits), Err(FairError::Invalid)));
+    }
+
+    #[test]
+    fn commitment_is_sha256_of_seed_and_nonce() {
+        let c = commitment(1, "abc");
+        assert_eq!(c.len(), 64);
+        assert_eq!(c, commitment(1, "abc"));
+        assert_ne!(c, commitment(2, "abc"));
+        assert_ne!(c, commitment(1, "abd"));
+    }
+}
@@ -0,0 +1,120 @@
+use std::fmt::Write;
+
+use leptos::prelude::*;
+use qrcodegen::{QrCode, QrCodeEcc};
+
+use crate::engine::cell_symbol;
+use crate::fair::{verify, FairError, FairProof, FairRound};
+use crate::pad::PadLayout;
+
+/// QR code 的 SVG 原始碼；文字過長無法編碼時為空字串
+fn qr_svg(text: &str) -> String {
+    let Ok(qr) = QrCode::encode_text(text, QrCodeEcc::Medium) else {
+        return String::new();
+    };
+    let border = 2;
+    let size = qr.size() + border * 2;
+    let mut path = String::new();
+    for y in 0..qr.size() {
+        for x in 0..qr.size() {
+            if qr.get_module(x, y) {
+                let _ = write!(path, "M{},{}h1v1h-1z", x + border, y + border);
+            }
+        }
+    }
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {size} {size}" shape-rendering="crispEdges"><rect width="{size}" height="{size}" fill="#fff"/><path d="{path}" fill="#000"/></svg>"##
+    )
+}
+
+/// 九宮格排列依當時的欄數以列分隔，例如 "123 / 456 / 789"；特殊格以網址中的字元表示
+fn format_layout(grid: &[u8], layout: PadLayout) -> String {
+    grid.chunks(layout.cols)
+        .map(|row| row.iter().map(|&d| cell_symbol(d)).collect::<String>())
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+#[component]
+pub fn FairPanel(round: ReadSignal<FairRound>, proof: Memo<Option<FairProof>>) -> impl IntoView {
+    let (input, set_input) = signal(String::new());
+    // 驗證結果：(是否通過, 說明, 重播出的每次排列)
+    let (result, set_result) = signal(None::<(bool, String, Vec<String>)>);
+
+    let on_verify = move |_| {
+        let outcome = serde_json::from_str::<FairProof>(input.get_untracked().trim())
+            .map_err(|_| FairError::Invalid)
+            .and_then(|p| verify(&p).map(|r| (p, r)));
+        set_result.set(Some(match outcome {
+            Ok((p, replay)) => {
+                let layouts = replay
+                    .layouts
+                    .iter()
+                    .map(|(grid, tf, layout)| {
+                        format!("{}（轉動 {}）", format_layout(grid, *layout), tf)
+                    })
+                    .collect();
+                (true, format!("驗證通過：金額 {}", p.amount), layouts)
+            }
+            Err(e) => (false, e.message().to_string(), Vec::new()),
+        }));
+    };
+
+    view! {
+        <div class="fair-panel">
+            <h2>"公正驗證"</h2>
+            <p class="fair-hint">"開局前公開種子的承諾值；開獎後公開種子，任何人都能重播出每一次的九宮格排列"</p>
+            <div class="fair-commitment">
+                <label>"承諾值 SHA-256"</label>
+                <code>{move || round.with(|r| r.commitment.clone())}</code>
+            </div>
+            <div class="fair-qr" inner_html=move || round.with(|r| qr_svg(&r.commitment))></div>
+
+            {move || {
+                proof
+                    .get()
+                    .map(|p| {
+                        let json = serde_json::to_string_pretty(&p).unwrap_or_default();
+                        view! {
+                            <div class="fair-reveal">
+                                <p>{format!("種子：{}", p.seed)}</p>
+                                <p>{format!("nonce：{}", p.nonce)}</p>
+                                <label>"驗證資料"</label>
+                                <textarea class="fair-proof" readonly prop:value=json></textarea>
+                            </div>
+                        }
+                    })
+            }}
+
+            <div class="fair-verify">
+                <h3>"驗證"</h3>
+                <textarea
+                    class="fair-proof"
+                    placeholder="貼上驗證資料（JSON）"
+                    prop:value=move || input.get()
+                    on:input=move |ev| set_input.set(event_target_value(&ev))
+                ></textarea>
+                <button class="option-btn" on:click=on_verify>
+                    "驗證"
+                </button>
+                {move || {
+                    result
+                        .get()
+                        .map(|(ok, message, layouts)| {
+                            view! {
+                                <p class="fair-result" class:ok=ok class:failed=!ok>
+                                    {message}
+                                </p>
+                                <ol class="fair-layouts">
+                                    {layouts
+                                        .into_iter()
+                                        .map(|l| view! { <li>{l}</li> })
+                                        .collect::<Vec<_>>()}
+                                </ol>
+                            }
+                        })
+                }}
+            </div>
+        </div>
+    }
+}
@@ -1,12 +1,34 @@
 use leptos::prelude::*;
+use web_sys::HtmlInputElement;
+
+use crate::animation::{prefers_reduced_motion, AnimationSpeed};
+use crate::engine::{DigitAlphabet, FillOrder, SpecialTile, SpecialTiles};
+use crate::pad::SpinStyle;
 
 #[component]
 pub fn GameOptions(
     no_repeat: ReadSignal<bool>,
     set_no_repeat: WriteSignal<bool>,
-    hidden_mode: ReadSignal<bool>,
-    set_hidden_mode: WriteSignal<bool>,
-    on_no_repeat_change: Callback<bool>,
+    no_undo: ReadSignal<bool>,
+    set_no_undo: WriteSignal<bool>,
+    face_down: ReadSignal<bool>,
+    set_face_down: WriteSignal<bool>,
+    fair: ReadSignal<bool>,
+    set_fair: WriteSignal<bool>,
+    fill_order: ReadSignal<FillOrder>,
+    set_fill_order: WriteSignal<FillOrder>,
+    alphabet: ReadSignal<DigitAlphabet>,
+    set_alphabet: WriteSignal<DigitAlphabet>,
+    spin_style: ReadSignal<SpinStyle>,
+    set_spin_style: WriteSignal<SpinStyle>,
+    speed: ReadSignal<AnimationSpeed>,
+    set_speed: WriteSignal<AnimationSpeed>,
+    specials: ReadSignal<SpecialTiles>,
+    set_specials: WriteSignal<SpecialTiles>,
+    consolation: ReadSignal<u64>,
+    set_consolation: WriteSignal<u64>,
+    on_seed_change: Callback<Option<u64>>,
+    seed_used: Memo<u64>,
 ) -> impl IntoView {
     view! {
         <div class="game-options">
@@ -14,22 +36,184 @@ pub fn GameOptions(
                 class="option-btn"
                 class:active=move || no_repeat.get()
                 on:click=move |_| {
-                    let new_val = !no_repeat.get();
-                    set_no_repeat.set(new_val);
-                    on_no_repeat_change.run(new_val);
+                    set_no_repeat.set(!no_repeat.get());
                 }
             >
                 "不允許重複"
             </button>
             <button
                 class="option-btn"
-                class:active=move || hidden_mode.get()
+                class:active=move || no_undo.get()
+                on:click=move |_| {
+                    set_no_undo.set(!no_undo.get());
+                }
+            >
+                "不可悔棋"
+            </button>
+            <button
+                class="option-btn"
+                class:active=move || face_down.get()
+                on:click=move |_| {
+                    set_face_down.set(!face_down.get());
+                }
+            >
+                "翻牌模式"
+            </button>
+            <button
+                class="option-btn"
+                class:active=move || fair.get()
                 on:click=move |_| {
-                    set_hidden_mode.set(!hidden_mode.get());
+                    set_fair.set(!fair.get());
                 }
             >
-                "隱藏模式"
+                "公正驗證"
             </button>
         </div>
+
+        <div class="fill-order">
+            <label>"填入順序: "</label>
+            {FillOrder::ALL
+                .into_iter()
+                .map(|order| {
+                    view! {
+                        <button
+                            class="option-btn"
+                            class:active=move || fill_order.get() == order
+                            on:click=move |_| set_fill_order.set(order)
+                        >
+                            {order.label()}
+                        </button>
+                    }
+                })
+                .collect::<Vec<_>>()}
+        </div>
+
+        <div class="spin-style">
+            <label>"轉動方式: "</label>
+            {SpinStyle::ALL
+                .into_iter()
+                .map(|style| {
+                    view! {
+                        <button
+                            class="option-btn"
+                            class:active=move || spin_style.get() == style
+                            on:click=move |_| set_spin_style.set(style)
+                        >
+                            {style.label()}
+                        </button>
+                    }
+                })
+                .collect::<Vec<_>>()}
+        </div>
+
+        <div class="animation-speed">
+            <label>"動畫速度: "</label>
+            {AnimationSpeed::ALL
+                .into_iter()
+                .map(|s| {
+                    view! {
+                        <button
+                            class="option-btn"
+                            class:active=move || speed.get() == s
+                            on:click=move |_| set_speed.set(s)
+                        >
+                            {s.label()}
+                        </button>
+                    }
+                })
+                .collect::<Vec<_>>()}
+            {prefers_reduced_motion()
+                .then(|| {
+                    view! { <p class="animation-speed-hint">"系統已設定減少動態效果，不播放動畫"</p> }
+                })}
+        </div>
+
+        <div class="special-tiles">
+            <label>"特殊格: "</label>
+            {SpecialTile::ALL
+                .into_iter()
+                .map(|tile| {
+                    view! {
+                        <button
+                            class="option-btn"
+                            class:active=move || specials.with(|s| s.contains(tile))
+                            on:click=move |_| set_specials.update(|s| s.toggle(tile))
+                        >
+                            {tile.label()}
+                        </button>
+                    }
+                })
+                .collect::<Vec<_>>()}
+            <Show when=move || specials.with(|s| s.contains(SpecialTile::Bust))>
+                <label>"安慰獎: "</label>
+                <input
+                    type="number"
+                    min="0"
+                    prop:value=move || consolation.get().to_string()
+                    on:change=move |ev| {
+                        match event_target_value(&ev).trim().parse::<u64>() {
+                            Ok(c) => set_consolation.set(c),
+                            // 無效輸入：還原顯示目前的安慰獎
+                            Err(_) => {
+                                event_target::<HtmlInputElement>(&ev)
+                                    .set_value(&consolation.get_untracked().to_string())
+                            }
+                        }
+                    }
+                />
+            </Show>
+        </div>
+
+        <div class="digit-alphabet">
+            <div class="digit-alphabet-chips">
+                {(0..=9u8)
+                    .map(|d| {
+                        view! {
+                            <button
+                                class="digit-chip"
+                                class:active=move || alphabet.with(|a| a.digits.contains(&d))
+                                on:click=move |_| set_alphabet.update(|a| a.toggle(d))
+                            >
+                                {d.to_string()}
+                            </button>
+                        }
+                    })
+                    .collect::<Vec<_>>()}
+            </div>
+            <Show when=move || alphabet.with(|a| a.digits.contains(&0))>
+                <button
+                    class="option-btn"
+                    class:active=move || alphabet.with(|a| a.leading_zero)
+                    on:click=move |_| set_alphabet.update(|a| a.leading_zero = !a.leading_zero)
+                >
+                    "首位可為 0"
+                </button>
+            </Show>
+        </div>
+
+        <Show when=move || !fair.get()>
+            <div class="seed-input">
+                <label>"種子: "</label>
+                <input
+                    type="text"
+                    inputmode="numeric"
+                    placeholder="隨機"
+                    prop:value=move || seed_used.get().to_string()
+                    on:change=move |ev| {
+                        let val = event_target_value(&ev);
+                        let val = val.trim();
+                        if val.is_empty() {
+                            on_seed_change.run(None);
+                        } else if let Ok(s) = val.parse::<u64>() {
+                            on_seed_change.run(Some(s));
+                        } else {
+                            // 無效輸入：還原顯示目前的種子
+                            event_target::<HtmlInputElement>(&ev)
+                                .set_value(&seed_used.get_untracked().to_string());
+                        }
+                    }
+                />
+            </div>
+        </Show>
     }
 }
@@ -0,0 +1,110 @@
+use leptos::prelude::*;
+
+use crate::engine::HiddenPolicy;
+
+/// 位數名稱，索引 0 為個位數
+const POSITION_NAMES: [&str; 9] = ["個", "十", "百", "千", "萬", "十萬", "百萬", "千萬", "億"];
+
+#[component]
+pub fn HiddenOptions(
+    policy: ReadSignal<HiddenPolicy>,
+    set_policy: WriteSignal<HiddenPolicy>,
+    digits_needed: Signal<u8>,
+) -> impl IntoView {
+    let choices = [
+        ("不隱藏", HiddenPolicy::Off),
+        ("最高位", HiddenPolicy::Highest),
+        ("全部", HiddenPolicy::All),
+        ("指定位數", HiddenPolicy::Positions(Vec::new())),
+        ("隨機位數", HiddenPolicy::Random(1)),
+    ];
+
+    view! {
+        <div class="hidden-options">
+            <div class="hidden-policy">
+                <label>"隱藏模式: "</label>
+                {choices
+                    .into_iter()
+                    .map(|(label, choice)| {
+                        let kind = std::mem::discriminant(&choice);
+                        view! {
+                            <button
+                                class="option-btn"
+                                class:active=move || {
+                                    policy.with(|p| std::mem::discriminant(p) == kind)
+                                }
+                                on:click=move |_| {
+                                    if policy.with_untracked(|p| std::mem::discriminant(p) != kind) {
+                                        set_policy.set(choice.clone());
+                                    }
+                                }
+                            >
+                                {label}
+                            </button>
+                        }
+                    })
+                    .collect::<Vec<_>>()}
+            </div>
+
+            <Show when=move || policy.with(|p| matches!(p, HiddenPolicy::Positions(_)))>
+                <div class="hidden-positions">
+                    {move || {
+                        (0..digits_needed.get() as usize)
+                            .rev()
+                            .map(|pos| {
+                                view! {
+                                    <button
+                                        class="digit-chip"
+                                        class:active=move || {
+                                            policy
+                                                .with(|p| {
+                                                    matches!(p, HiddenPolicy::Positions(list) if list.contains(&pos))
+                                                })
+                                        }
+                                        on:click=move |_| {
+                                            set_policy
+                                                .update(|p| {
+                                                    if let HiddenPolicy::Positions(list) = p {
+                                                        if let Some(i) = list.iter().position(|&x| x == pos) {
+                                                            list.remove(i);
+                                                        } else {
+                                                            list.push(pos);
+                                                            list.sort_unstable();
+                                                        }
+                                                    }
+                                                })
+                                        }
+                                    >
+                                        {POSITION_NAMES[pos]}
+                                    </button>
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                    }}
+                </div>
+            </Show>
+
+            <Show when=move || policy.with(|p| matches!(p, HiddenPolicy::Random(_)))>
+                <div class="hidden-random">
+                    <label>"隱藏幾位: "</label>
+                    <input
+                        type="number"
+                        min="1"
+                        max="9"
+                        prop:value=move || {
+                            policy
+                                .with(|p| match p {
+                                    HiddenPolicy::Random(n) => n.to_string(),
+                                    _ => String::new(),
+                                })
+                        }
+                        on:input=move |ev| {
+                            let val = event_target_value(&ev).parse::<u8>().unwrap_or(1);
+                            set_policy.set(HiddenPolicy::Random(val.clamp(1, 9)));
+                        }
+                    />
+                </div>
+            </Show>
+        </div>
+    }
+}
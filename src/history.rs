@@ -0,0 +1,274 @@
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+
+use crate::engine::cell_symbol;
+
+const STORAGE_KEY: &str = "digit-draw-history";
+
+/// 一局完成的抽選紀錄
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoundRecord {
+    /// 完成時間（ISO 8601）
+    pub timestamp: String,
+    /// 開獎金額（含特殊格的效果）
+    pub amount: u64,
+    /// 各位數的數字（由個位數開始）
+    pub digits: Vec<u8>,
+    /// 各位數選取當下的九宮格排列（由個位數開始），含特殊格
+    pub layouts: Vec<Vec<u8>>,
+    /// 填入順序（"ones"、"high"、"random"、"pick"）
+    #[serde(default)]
+    pub fill_order: String,
+    /// 依選取順序填入的位數（0 為個位數）
+    #[serde(default)]
+    pub pick_order: Vec<usize>,
+    pub no_repeat: bool,
+    pub hidden_mode: bool,
+    /// 隱藏模式設定（例如 "1"、"all"、"pos:0,2"、"random:2"）
+    #[serde(default)]
+    pub hidden_policy: String,
+    /// 翻牌模式
+    #[serde(default)]
+    pub face_down: bool,
+    /// 公正驗證的承諾值與 nonce；未開啟時為 `None`
+    #[serde(default)]
+    pub commitment: Option<String>,
+    #[serde(default)]
+    pub nonce: Option<String>,
+    /// 公正驗證的操作紀錄（例如 "S,P3,T,A,P7"）
+    #[serde(default)]
+    pub actions: String,
+    pub digits_needed: u8,
+    /// 金額範圍模式的下限；其他模式為 `None`
+    #[serde(default)]
+    pub min_amount: Option<u64>,
+    /// 最大金額（或金額範圍）模式的上限；位數模式為 `None`
+    pub max_amount: Option<u64>,
+    /// 可用數字（例如 "123456789"）
+    pub alphabet: String,
+    pub seed: u64,
+    /// 數字權重（依序為數字 0-9 的權重，例如 "1111111111"）
+    #[serde(default)]
+    pub weights: String,
+    /// 依按下順序用過的特殊格（例如 "x2,redraw"）
+    #[serde(default)]
+    pub tiles: String,
+}
+
+/// 目前時間（ISO 8601）
+pub fn now_iso() -> String {
+    js_sys::Date::new_0().to_iso_string().into()
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window().and_then(|w| w.local_storage().ok().flatten())
+}
+
+/// 從 localStorage 讀取紀錄；格式錯誤時視為沒有紀錄
+pub fn load_history() -> Vec<RoundRecord> {
+    local_storage()
+        .and_then(|s| s.get_item(STORAGE_KEY).ok().flatten())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// 寫入 localStorage
+pub fn save_history(records: &[RoundRecord]) {
+    let Some(storage) = local_storage() else {
+        return;
+    };
+    if let Ok(json) = serde_json::to_string(records) {
+        let _ = storage.set_item(STORAGE_KEY, &json);
+    }
+}
+
+fn to_json(records: &[RoundRecord]) -> String {
+    serde_json::to_string_pretty(records).unwrap_or_default()
+}
+
+/// CSV 欄位（RFC 4180）：一律加上雙引號，內容中的雙引號重複一次。
+/// 隱藏模式（"pos:0,2"）、操作紀錄與特殊格都含有逗號
+fn csv_field(value: impl ToString) -> String {
+    format!("\"{}\"", value.to_string().replace('"', "\"\""))
+}
+
+fn to_csv(records: &[RoundRecord]) -> String {
+    let join_digits = |cells: &[u8]| cells.iter().map(|&d| cell_symbol(d)).collect::<String>();
+    let mut csv = String::from(
+        "timestamp,amount,digits,layouts,no_repeat,hidden_mode,digits_needed,min_amount,max_amount,alphabet,seed,weights,fill_order,pick_order,hidden_policy,face_down,commitment,nonce,actions,tiles\n",
+    );
+    for r in records {
+        let digits = r
+            .digits
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>()
+            .join("-");
+        let layouts = r
+            .layouts
+            .iter()
+            .map(|l| join_digits(l))
+            .collect::<Vec<_>>()
+            .join("|");
+        let pick_order = r
+            .pick_order
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join("-");
+        let min_amount = r.min_amount.map(|m| m.to_string()).unwrap_or_default();
+        let max_amount = r.max_amount.map(|m| m.to_string()).unwrap_or_default();
+        let fields = [
+            csv_field(&r.timestamp),
+            csv_field(r.amount),
+            csv_field(digits),
+            csv_field(layouts),
+            csv_field(r.no_repeat),
+            csv_field(r.hidden_mode),
+            csv_field(r.digits_needed),
+            csv_field(min_amount),
+            csv_field(max_amount),
+            csv_field(&r.alphabet),
+            csv_field(r.seed),
+            csv_field(&r.weights),
+            csv_field(&r.fill_order),
+            csv_field(pick_order),
+            csv_field(&r.hidden_policy),
+            csv_field(r.face_down),
+            csv_field(r.commitment.as_deref().unwrap_or_default()),
+            csv_field(r.nonce.as_deref().unwrap_or_default()),
+            csv_field(&r.actions),
+            csv_field(&r.tiles),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// 以 data URL 觸發瀏覽器下載
+fn download(filename: &str, mime: &str, content: &str) {
+    let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+        return;
+    };
+    let Ok(anchor) = document.create_element("a") else {
+        return;
+    };
+    let href = format!(
+        "data:{};charset=utf-8,{}",
+        mime,
+        js_sys::encode_uri_component(content)
+    );
+    let _ = anchor.set_attribute("href", &href);
+    let _ = anchor.set_attribute("download", filename);
+    if let Ok(anchor) = anchor.dyn_into::<web_sys::HtmlElement>() {
+        anchor.click();
+    }
+}
+
+#[component]
+pub fn HistoryPanel(
+    history: ReadSignal<Vec<RoundRecord>>,
+    set_history: WriteSignal<Vec<RoundRecord>>,
+) -> impl IntoView {
+    let on_export_csv = move |_| {
+        history.with(|h| download("digit-draw-history.csv", "text/csv", &to_csv(h)));
+    };
+    let on_export_json = move |_| {
+        history.with(|h| download("digit-draw-history.json", "application/json", &to_json(h)));
+    };
+    let on_clear = move |_| {
+        set_history.set(Vec::new());
+        save_history(&[]);
+    };
+
+    view! {
+        <div class="history-panel">
+            <h2>"抽選紀錄"</h2>
+            <ol class="history-list" reversed>
+                {move || {
+                    history
+                        .get()
+                        .into_iter()
+                        .rev()
+                        .map(|r| {
+                            let range = match (r.min_amount, r.max_amount) {
+                                (Some(lo), Some(hi)) => format!("{}-{}", lo, hi),
+                                (None, Some(hi)) => format!("最大 {}", hi),
+                                _ => format!("{} 位數", r.digits_needed),
+                            };
+                            view! {
+                                <li class="history-item">
+                                    <span class="history-amount">{r.amount.to_string()}</span>
+                                    <span class="history-meta">{range}</span>
+                                    <span class="history-meta">{r.timestamp}</span>
+                                </li>
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                }}
+            </ol>
+            <div class="history-actions">
+                <button
+                    class="option-btn"
+                    on:click=on_export_csv
+                    disabled=move || history.with(|h| h.is_empty())
+                >
+                    "匯出 CSV"
+                </button>
+                <button
+                    class="option-btn"
+                    on:click=on_export_json
+                    disabled=move || history.with(|h| h.is_empty())
+                >
+                    "匯出 JSON"
+                </button>
+                <button
+                    class="option-btn"
+                    on:click=on_clear
+                    disabled=move || history.with(|h| h.is_empty())
+                >
+                    "清除紀錄"
+                </button>
+            </div>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_with_commas_are_quoted() {
+        let record = RoundRecord {
+            timestamp: "2024-01-01T00:00:00.000Z".to_string(),
+            amount: 123,
+            digits: vec![3, 2, 1],
+            layouts: vec![vec![1, 2, 3]; 3],
+            fill_order: "ones".to_string(),
+            pick_order: vec![0, 1, 2],
+            no_repeat: false,
+            hidden_mode: true,
+            hidden_policy: "pos:0,2".to_string(),
+            face_down: false,
+            commitment: None,
+            nonce: None,
+            actions: "S,P3,T".to_string(),
+            digits_needed: 3,
+            min_amount: None,
+            max_amount: Some(999),
+            alphabet: "123456789".to_string(),
+            seed: 1,
+            weights: "1111111111".to_string(),
+            tiles: "x2,redraw".to_string(),
+        };
+        let csv = to_csv(&[record]);
+        let row = csv.lines().nth(1).unwrap();
+        assert!(row.contains(r#""pos:0,2""#));
+        assert!(row.contains(r#""S,P3,T""#));
+        assert!(row.ends_with(r#""x2,redraw""#));
+        assert_eq!(csv_field(r#"a"b"#), r#""a""b""#);
+    }
+}
@@ -0,0 +1,10 @@
+//! 鍵盤快捷鍵共用的判斷
+
+use wasm_bindgen::JsCast;
+
+/// 正在輸入框內打字時，快捷鍵保留給瀏覽器原本的行為
+pub fn typing_in_field(ev: &web_sys::KeyboardEvent) -> bool {
+    ev.target()
+        .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+        .is_some_and(|el| matches!(el.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT"))
+}
@@ -0,0 +1,6 @@
+//! 與畫面無關的遊戲核心：網頁版（`main.rs`）與命令列版（`bin/digit-draw-cli.rs`）共用
+
+pub mod analysis;
+pub mod engine;
+pub mod fair;
+pub mod pad;
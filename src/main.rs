@@ -1,10 +1,23 @@
 mod amount_display;
+mod analysis_panel;
+mod animation;
 mod app;
+mod budget;
+mod config;
+mod digit_weights;
+mod fair_panel;
 mod game_options;
+mod hidden_options;
+mod history;
+mod keyboard;
 mod number_pad;
 mod range_config;
+mod red_envelope;
+mod session;
+mod undo;
 
 use app::App;
+use digit_draw::{analysis, engine, fair, pad};
 
 fn main() {
     console_error_panic_hook::set_once();
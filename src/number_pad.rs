@@ -1,30 +1,18 @@
-use crate::range_config::LimitMode;
+use crate::animation::{AnimationSpeed, Animator, Frame, CELL_MS, FLIP_MS, SLIDE_MS, TURN_MS};
+use crate::engine::{cell_label, DigitWeights, Event, GameState, SpecialTile};
+use crate::fair::PadAction;
+use crate::keyboard::typing_in_field;
+use crate::pad::{self, PadLayout, RotateDir, Spin, SpinStyle, TRANSFORM_COUNT};
+use leptos::ev;
 use leptos::prelude::*;
-use rand::seq::SliceRandom;
-use rand::thread_rng;
-use rand::Rng;
-use wasm_bindgen::closure::Closure;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::time::Duration;
 use wasm_bindgen::JsCast;
 
-/// 計算已選數字組成的金額（由個位數開始）
-fn current_amount(selected: &[u8]) -> u64 {
-    selected
-        .iter()
-        .enumerate()
-        .map(|(i, &n)| n as u64 * 10u64.pow(i as u32))
-        .sum()
-}
-
-/// 如果再選 `candidate` 放在下一個位數，金額是否會超過上限
-fn would_exceed(selected: &[u8], candidate: u8, max_amount: u64) -> bool {
-    let next_pos = selected.len() as u32;
-    let new_amount = current_amount(selected) + candidate as u64 * 10u64.pow(next_pos);
-    new_amount > max_amount
-}
-
 /// (grid transform, cell counter-transform)
 /// 使用 rotateY/rotateX 取代 scaleX(-1)/scaleY(-1)，讓 transition 產生 3D 翻轉動畫
-const TRANSFORM_PAIRS: [(&str, &str); 8] = [
+const TRANSFORM_PAIRS: [(&str, &str); TRANSFORM_COUNT] = [
     ("", ""),
     ("rotate(90deg)", "rotate(-90deg)"),
     ("rotate(180deg)", "rotate(-180deg)"),
@@ -41,205 +29,450 @@ const TRANSFORM_PAIRS: [(&str, &str); 8] = [
     ),
 ];
 
-/// 隨機選取一個與 `current` 不同的 transform 配對
-fn random_transform(current: &str) -> (String, String) {
-    let candidates: Vec<_> = TRANSFORM_PAIRS
-        .iter()
-        .filter(|(tf, _)| *tf != current)
-        .collect();
-    let pair = candidates.choose(&mut thread_rng()).unwrap();
-    (pair.0.to_string(), pair.1.to_string())
-}
-
-/// 轉動方向
-#[derive(Clone, Copy)]
-enum RotateDir {
-    /// 所有 row 向右循環
-    RowRight,
-    /// 所有 column 向下循環
-    ColDown,
-}
-
-/// 隨機選擇方向
-fn random_rotate_dir() -> RotateDir {
-    if thread_rng().gen_bool(0.5) {
-        RotateDir::RowRight
-    } else {
-        RotateDir::ColDown
-    }
-}
-
-/// 依方向將所有 row/column 做循環位移
-fn rotate_lines(nums: &mut [u8], dir: RotateDir) {
-    let lines: [[usize; 3]; 3] = match dir {
-        RotateDir::RowRight => [[0, 1, 2], [3, 4, 5], [6, 7, 8]],
-        RotateDir::ColDown => [[0, 3, 6], [1, 4, 7], [2, 5, 8]],
-    };
-
-    for indices in &lines {
-        // [a, b, c] -> [c, a, b]
-        let last = nums[indices[2]];
-        nums[indices[2]] = nums[indices[1]];
-        nums[indices[1]] = nums[indices[0]];
-        nums[indices[0]] = last;
-    }
+/// 按下九宮格的結果
+pub struct PadPress {
+    pub event: Event,
+    /// 按下前的遊戲狀態
+    pub before: GameState,
+    /// 按下前的九宮格排列（隱藏模式依權重換格之前）
+    pub grid_before: Vec<u8>,
+    /// 按下後的九宮格排列
+    pub layout: Vec<u8>,
 }
 
 #[component]
 pub fn NumberPad(
-    mode: ReadSignal<LimitMode>,
-    digits_needed: ReadSignal<u8>,
-    max_amount: ReadSignal<u64>,
-    selected_numbers: ReadSignal<Vec<u8>>,
-    set_selected_numbers: WriteSignal<Vec<u8>>,
-    hidden_mode: ReadSignal<bool>,
-    no_repeat: ReadSignal<bool>,
-    game_finished: ReadSignal<bool>,
+    game: ReadSignal<GameState>,
+    set_game: WriteSignal<GameState>,
+    /// 以 `$` 遮住所有格子
+    masked: Signal<bool>,
+    /// 預算用完等原因，不能再選取
+    locked: Signal<bool>,
+    /// 翻牌模式：未選的格子蓋著，每局開始與每次選取後自動洗牌
+    face_down: ReadSignal<bool>,
+    seed: Memo<u64>,
+    /// 九宮格排列
+    grid_numbers: ReadSignal<Vec<u8>>,
+    set_grid_numbers: WriteSignal<Vec<u8>>,
+    /// 九宮格 transform（`TRANSFORM_PAIRS` 的索引）
+    grid_transform: ReadSignal<usize>,
+    set_grid_transform: WriteSignal<usize>,
+    pad_size: ReadSignal<u32>,
+    set_pad_size: WriteSignal<u32>,
+    /// 九宮格的列數與欄數
+    layout: Memo<PadLayout>,
+    /// 選擇列數與欄數；`None` 為預設排法
+    set_layout: WriteSignal<Option<PadLayout>>,
+    weights: ReadSignal<DigitWeights>,
+    /// 隨機轉動的方式
+    spin_style: ReadSignal<SpinStyle>,
+    /// 動畫速度；系統設定減少動態效果時已是關閉
+    speed: Signal<AnimationSpeed>,
+    /// 轉動與轉動一排的動畫；播放中不能選取
+    animator: Animator,
+    /// 每次選取或取消後回報
+    on_press: Callback<PadPress>,
+    /// 每個會用到亂數的操作，依發生順序回報（公正驗證重播用）
+    on_action: Callback<PadAction>,
 ) -> impl IntoView {
-    let (grid_numbers, set_grid_numbers) = signal(vec![1u8, 2, 3, 4, 5, 6, 7, 8, 9]);
-    let (pad_size, set_pad_size) = signal(100u32);
-    let (grid_transform, set_grid_transform) = signal((String::new(), String::new()));
-    // 轉動一排動畫：CSS class 名稱（"rotate-row" 或 "rotate-col"）
-    let (rotate_anim, set_rotate_anim) = signal(String::new());
-    let (animating, set_animating) = signal(false);
-
-    let on_shuffle = move |_| {
-        let mut nums = grid_numbers.get();
-        let original = nums.clone();
-        loop {
-            nums.shuffle(&mut thread_rng());
-            if nums != original {
-                break;
-            }
+    // 所有隨機操作共用同一個由種子決定的亂數產生器，相同種子與操作順序可重現盤面
+    let rng = StoredValue::new(ChaCha8Rng::seed_from_u64(seed.get_untracked()));
+    // 關閉動畫時結束進行中的動畫，否則等不到結束事件
+    Effect::new(move |_| {
+        if speed.get() == AnimationSpeed::Off {
+            animator.cancel();
         }
+    });
+    // 自動抽選：是否進行中、每次抽選間隔（毫秒）、剛抽中的格子索引（用於高亮動畫）。
+    // 記錄格子而非數字：看不到數字時依權重換格，抽中的數字可能換到別的格子
+    let (auto_running, set_auto_running) = signal(false);
+    let (auto_delay, set_auto_delay) = signal(800u32);
+    let (auto_highlight, set_auto_highlight) = signal(None::<usize>);
+    let auto_timer = StoredValue::new(None::<IntervalHandle>);
+
+    let shuffle_grid = move || {
+        let mut nums = grid_numbers.get_untracked();
+        rng.update_value(|r| pad::shuffle(&mut nums, r));
         set_grid_numbers.set(nums);
     };
+    // 洗牌會換掉整個排列，進行中的動畫直接結束
+    let on_shuffle = move || {
+        animator.cancel();
+        on_action.run(PadAction::Shuffle);
+        shuffle_grid();
+    };
+
+    // 種子變更時重設亂數產生器；翻牌模式在新的一局開始、換了種子、剛開啟翻牌模式或元件剛建立時洗牌，
+    // 避免在觀眾已經看過的排列（例如預設的 123/456/789）上翻牌。
+    // 兩件事放在同一個 effect，確保先重設亂數再洗牌。第一次執行時也重設：
+    // 元件建立後、任何操作之前種子可能已經換過（例如公正驗證的第一局）
+    Effect::new(move |prev: Option<(u64, bool, bool)>| {
+        let s = seed.get();
+        let empty = game.with(GameState::is_empty);
+        let down = face_down.get();
+        let reseeded = prev.is_none_or(|(prev_seed, _, _)| prev_seed != s);
+        if reseeded {
+            rng.set_value(ChaCha8Rng::seed_from_u64(s));
+        }
+        let fresh = match prev {
+            None => true,
+            Some((_, was_empty, was_down)) => !was_empty || reseeded || !was_down,
+        };
+        if down && empty && fresh {
+            on_action.run(PadAction::Shuffle);
+            shuffle_grid();
+        }
+        (s, empty, down)
+    });
 
     let handle_click = move |num: u8| {
-        if game_finished.get() {
+        if animator.is_busy_untracked() || locked.get_untracked() {
             return;
         }
-        let mut current = selected_numbers.get();
-        let no_rep = no_repeat.get();
-
-        if no_rep && current.contains(&num) {
-            current.retain(|&n| n != num);
-        } else if (current.len() as u8) < digits_needed.get() {
-            if mode.get() == LimitMode::ByMaxAmount && would_exceed(&current, num, max_amount.get())
+        on_action.run(PadAction::Press(num));
+        let mut state = game.get_untracked();
+        let before = state.clone();
+        let grid_before = grid_numbers.get_untracked();
+        let mut num = num;
+        // 隱藏模式（或翻牌模式）時，依權重決定被點的格子翻開哪個數字
+        if masked.get_untracked() || face_down.get_untracked() {
+            let w = weights.get_untracked();
+            let mut grid = grid_before.clone();
+            if let Some(d) =
+                rng.try_update_value(|r| pad::weighted_remap(&state, &mut grid, num, &w, r))
             {
-                return;
+                if grid != grid_before {
+                    set_grid_numbers.set(grid);
+                }
+                num = d;
             }
-            current.push(num);
         }
-        set_selected_numbers.set(current);
+        if let Some(Ok(event)) = rng.try_update_value(|r| state.press(num, r)) {
+            set_game.set(state);
+            on_press.run(PadPress {
+                event,
+                before,
+                grid_before,
+                layout: grid_numbers.get_untracked(),
+            });
+            // 翻牌模式每次選取後洗牌；「重抽」特殊格重新洗牌後再選一次
+            if face_down.get_untracked() || event == Event::Tile(SpecialTile::Redraw) {
+                shuffle_grid();
+            }
+        }
+    };
+
+    let stop_auto = move || {
+        if let Some(handle) = auto_timer.try_update_value(Option::take).flatten() {
+            handle.clear();
+        }
+        set_auto_running.set(false);
     };
 
-    let on_spin = move |_| {
-        if animating.get() {
+    // 從目前可選的格子中隨機挑一個按下；沒有可選的格子或已填滿時停止
+    let auto_pick = move || {
+        if animator.is_busy_untracked() {
             return;
         }
-        let mut rng = thread_rng();
-        if rng.gen_bool(0.5) {
-            let (ref current, _) = grid_transform.get();
-            set_grid_transform.set(random_transform(current));
-        } else {
-            let dir = random_rotate_dir();
-            let anim_class = match dir {
-                RotateDir::RowRight => "rotate-row",
-                RotateDir::ColDown => "rotate-col",
-            };
-            // 1) 加上 CSS animation class
-            set_rotate_anim.set(anim_class.to_string());
-            set_animating.set(true);
-            // 2) 動畫結束後：更新數字、移除 class
-            let window = web_sys::window().unwrap();
-            let cb = Closure::once(move || {
-                let mut nums = grid_numbers.get();
-                rotate_lines(&mut nums, dir);
+        if locked.get_untracked() {
+            stop_auto();
+            return;
+        }
+        let state = game.get_untracked();
+        let grid = grid_numbers.get_untracked();
+        let picked = rng
+            .try_update_value(|r| pad::auto_choice(&state, &grid, r))
+            .flatten();
+        let Some(num) = picked else {
+            stop_auto();
+            return;
+        };
+        on_action.run(PadAction::AutoPick);
+        set_auto_highlight.set(grid.iter().position(|&n| n == num));
+        handle_click(num);
+        if game.with_untracked(|g| g.is_complete()) {
+            stop_auto();
+        }
+    };
+
+    let on_auto = move |_| {
+        if auto_running.get() {
+            stop_auto();
+            return;
+        }
+        let delay = Duration::from_millis(auto_delay.get() as u64);
+        if let Ok(handle) = set_interval_with_handle(auto_pick, delay) {
+            auto_timer.set_value(Some(handle));
+            set_auto_running.set(true);
+        }
+    };
+
+    // 重新開始（或規則變更）時停止自動抽選、結束動畫並清除高亮
+    Effect::new(move |_| {
+        if game.with(|g| g.is_empty()) {
+            stop_auto();
+            animator.cancel();
+            set_auto_highlight.set(None);
+        }
+    });
+
+    on_cleanup(stop_auto);
+
+    // 排列與 transform 立即更新；動畫播放時畫面先顯示轉動前的盤面。動畫進行中再轉動則排隊
+    let on_spin = move || {
+        let grid = grid_numbers.get_untracked();
+        let current = grid_transform.get_untracked();
+        let style = spin_style.get_untracked();
+        let shape = layout.get_untracked();
+        let Some(result) =
+            rng.try_update_value(|r| pad::spin(current, style, shape, grid.len(), r))
+        else {
+            return;
+        };
+        on_action.run(PadAction::Spin(style));
+        match result {
+            Spin::Transform(next) => {
+                set_grid_transform.set(next);
+                animator.play(Frame {
+                    grid,
+                    transform: next,
+                    class: None,
+                    offsets: Vec::new(),
+                });
+            }
+            Spin::Rotate(dir) => {
+                let mut nums = grid.clone();
+                pad::rotate_lines(&mut nums, shape, dir);
                 set_grid_numbers.set(nums);
-                set_rotate_anim.set(String::new());
-                set_animating.set(false);
-            });
-            window
-                .set_timeout_with_callback_and_timeout_and_arguments_0(
-                    cb.as_ref().unchecked_ref(),
-                    420,
-                )
-                .unwrap();
-            cb.forget();
+                animator.play(Frame {
+                    grid,
+                    transform: current,
+                    class: Some(match dir {
+                        RotateDir::RowRight => "rotate-row",
+                        RotateDir::ColDown => "rotate-col",
+                    }),
+                    offsets: Vec::new(),
+                });
+            }
+            Spin::Permute(perm) => {
+                let mut nums = grid.clone();
+                pad::permute(&mut nums, shape, perm);
+                set_grid_numbers.set(nums);
+                animator.play(Frame {
+                    offsets: pad::permutation_offsets(perm, shape, grid.len()),
+                    grid,
+                    transform: current,
+                    class: Some("permute"),
+                });
+            }
         }
     };
 
+    // 鍵盤操作：聚焦的格子以索引表示，數字鍵與方向鍵都依畫面上（套用 transform 後）的位置
+    let (focused, set_focused) = signal(None::<usize>);
+    let pick_at = move |index: usize| {
+        if auto_running.get_untracked() {
+            return;
+        }
+        if let Some(num) = grid_numbers.with_untracked(|g| g.get(index).copied()) {
+            set_focused.set(Some(index));
+            handle_click(num);
+        }
+    };
+    // 往 (d_row, d_col) 方向移動，跳過最後一列不滿時的空位
+    let move_focus = move |d_row: i32, d_col: i32| {
+        let len = grid_numbers.with_untracked(Vec::len);
+        let shape = layout.get_untracked();
+        let transform = grid_transform.get_untracked();
+        let Some(current) = focused.get_untracked() else {
+            set_focused.set(pad::cell_at(len, shape, transform, 0, 0));
+            return;
+        };
+        let (rows, cols) = pad::screen_size(shape, transform);
+        let (mut row, mut col) = pad::screen_position(current, shape, transform);
+        loop {
+            let (r, c) = (row as i32 + d_row, col as i32 + d_col);
+            if r < 0 || c < 0 || r >= rows as i32 || c >= cols as i32 {
+                return;
+            }
+            (row, col) = (r as usize, c as usize);
+            if let Some(index) = pad::cell_at(len, shape, transform, row, col) {
+                set_focused.set(Some(index));
+                return;
+            }
+        }
+    };
+    let keydown = window_event_listener(ev::keydown, move |ev| {
+        if ev.repeat() || ev.ctrl_key() || ev.meta_key() || ev.alt_key() || typing_in_field(&ev) {
+            return;
+        }
+        // 以實體按鍵位置判斷，NumLock 關閉時數字鍵盤也能使用
+        let code = ev.code();
+        let digit = code
+            .strip_prefix("Numpad")
+            .or_else(|| code.strip_prefix("Digit"))
+            .and_then(|d| d.parse::<u32>().ok());
+        if let Some(d) = digit {
+            let len = grid_numbers.with_untracked(Vec::len);
+            let shape = layout.get_untracked();
+            let transform = grid_transform.get_untracked();
+            if let Some(index) =
+                pad::numpad_position(d).and_then(|(r, c)| pad::cell_at(len, shape, transform, r, c))
+            {
+                pick_at(index);
+            }
+            ev.prevent_default();
+            return;
+        }
+        match ev.key().as_str() {
+            "ArrowUp" => move_focus(-1, 0),
+            "ArrowDown" => move_focus(1, 0),
+            "ArrowLeft" => move_focus(0, -1),
+            "ArrowRight" => move_focus(0, 1),
+            "Enter" => match focused.get_untracked() {
+                Some(index) => pick_at(index),
+                None => return,
+            },
+            "s" | "S" => on_shuffle(),
+            "r" | "R" => on_spin(),
+            _ => return,
+        }
+        ev.prevent_default();
+    });
+    on_cleanup(move || keydown.remove());
+
+    // 畫面上的排列與 transform：動畫播放中為該段動畫的盤面
+    let shown_grid = move || match animator.frame() {
+        Some(frame) => frame.grid,
+        None => grid_numbers.get(),
+    };
+    let shown_transform = move || match animator.frame() {
+        Some(frame) => frame.transform,
+        None => grid_transform.get(),
+    };
+
+    let turn_transition = move || {
+        format!(
+            "transition: transform {}ms ease",
+            speed.get().scale(TURN_MS)
+        )
+    };
+
     let pad_style = move || {
-        let size = format!("max-width: {}%", pad_size.get());
-        let (ref tf, _) = grid_transform.get();
+        let size = format!(
+            "max-width: {}%; grid-template-columns: repeat({}, 1fr)",
+            pad_size.get(),
+            layout.get().cols
+        );
+        let (tf, _) = TRANSFORM_PAIRS[shown_transform()];
         if tf.is_empty() {
-            format!("{}; transition: transform 0.6s ease", size)
+            format!("{}; {}", size, turn_transition())
         } else {
-            format!(
-                "{}; transform: {}; transition: transform 0.6s ease",
-                size, tf
-            )
+            format!("{}; transform: {}; {}", size, tf, turn_transition())
         }
     };
 
     let text_style = move || {
-        let (_, ref counter) = grid_transform.get();
+        let (_, counter) = TRANSFORM_PAIRS[shown_transform()];
         if counter.is_empty() {
-            "transition: transform 0.6s ease".to_string()
+            turn_transition()
         } else {
-            format!("transform: {}; transition: transform 0.6s ease", counter)
+            format!("transform: {}; {}", counter, turn_transition())
         }
     };
 
+    // CSS animation 與 transition 的時間（轉動一排、翻牌、自動抽中閃爍、格子狀態變化）
+    let animation_vars = move || {
+        let s = speed.get();
+        format!(
+            "--slide-duration: {}ms; --flip-duration: {}ms; --cell-transition: {}ms",
+            s.scale(SLIDE_MS),
+            s.scale(FLIP_MS),
+            s.scale(CELL_MS)
+        )
+    };
+
     view! {
-        <div class="number-pad">
+        <div
+            class="number-pad"
+            class:locked=move || locked.get()
+            class:animating=move || animator.is_busy()
+            style=animation_vars
+        >
             <div
-                class=move || {
-                    let anim = rotate_anim.get();
-                    if anim.is_empty() {
-                        "number-pad-grid".to_string()
-                    } else {
-                        format!("number-pad-grid {}", anim)
-                    }
+                class=move || match animator.frame().and_then(|f| f.class) {
+                    Some(anim) => format!("number-pad-grid {}", anim),
+                    None => "number-pad-grid".to_string(),
                 }
                 style=pad_style
+                on:transitionend=move |ev| {
+                    // 只看九宮格本身的 transform，忽略格子的 hover 等 transition
+                    let own = ev
+                        .target()
+                        .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+                        .is_some_and(|el| el.class_name().split_whitespace().any(|c| c == "number-pad-grid"));
+                    let turning = animator.frame().is_some_and(|f| f.class.is_none());
+                    if own && turning && ev.property_name() == "transform" {
+                        animator.advance();
+                    }
+                }
             >
                 {move || {
-                    let selected = selected_numbers.get();
-                    let finished = game_finished.get();
-                    let needed = digits_needed.get();
-                    let current_mode = mode.get();
-                    let amt_limit = max_amount.get();
-                    let no_rep = no_repeat.get();
-                    grid_numbers
-                        .get()
+                    let state = game.get();
+                    let finished = state.is_finished();
+                    // 剛翻開的數字（翻牌動畫）
+                    let latest = state
+                        .pick_order()
+                        .last()
+                        .and_then(|&pos| state.slots()[pos]);
+                    let offsets = animator.frame().map(|f| f.offsets).unwrap_or_default();
+                    let grid = shown_grid();
+                    // 格子數不足的位置留空，維持列數與欄數（轉動時的中心與鍵盤位置才會正確）
+                    let blanks = layout.with(|l| l.rows * l.cols).saturating_sub(grid.len());
+                    let cells = grid
                         .into_iter()
-                        .map(|num| {
-                            let is_selected = selected.contains(&num);
-                            let at_capacity = selected.len() as u8 >= needed;
-                            let exceeds_limit = current_mode == LimitMode::ByMaxAmount
-                                && !at_capacity && would_exceed(&selected, num, amt_limit);
-                            let is_disabled = if no_rep {
-                                finished || (!is_selected && at_capacity)
-                                    || (!is_selected && exceeds_limit)
-                            } else {
-                                finished || at_capacity || exceeds_limit
-                            };
-                            let hidden = hidden_mode.get() && !finished;
+                        .enumerate()
+                        .map(|(index, num)| {
+                            let cell = state.cell_state(num);
+                            // 翻牌模式：選過的格子翻開，開獎後全部翻開
+                            let covered = face_down.get() && !cell.selected && !finished;
+                            let hidden = masked.get() || covered;
                             let display_text = if hidden {
                                 "$".to_string()
                             } else {
-                                num.to_string()
+                                cell_label(num)
                             };
+                            let special = SpecialTile::from_code(num).is_some() && !hidden;
                             let ts = text_style();
+                            let move_style = offsets
+                                .get(index)
+                                .map(|(dx, dy)| format!("--dx: {}; --dy: {}", dx, dy));
                             view! {
                                 <button
                                     class="pad-cell"
-                                    class:selected=is_selected
-                                    class:disabled=is_disabled
-                                    class:exceeds=exceeds_limit
-                                    on:click=move |_| handle_click(num)
+                                    style=move_style
+                                    class:special-tile=special
+                                    class:selected=cell.selected
+                                    class:disabled=cell.disabled || locked.get()
+                                    class:exceeds=cell.exceeds
+                                    class:auto-pick=auto_highlight.get() == Some(index)
+                                    class:face-down=covered
+                                    class:flip-in=face_down.get() && latest == Some(num)
+                                    class:keyboard-focus=focused.get() == Some(index)
+                                    on:click=move |_| {
+                                        if !auto_running.get_untracked() {
+                                            handle_click(num);
+                                        }
+                                    }
+                                    on:animationend=move |ev| {
+                                        // 所有格子同時播放，只以第一格的結束為準
+                                        let name = ev.animation_name();
+                                        if index == 0
+                                            && matches!(name.as_str(), "slide-right" | "slide-down" | "permute-move")
+                                        {
+                                            animator.advance();
+                                        }
+                                    }
                                 >
                                     <span class="pad-cell-text" style=ts>
                                         {display_text}
@@ -247,16 +480,67 @@ pub fn NumberPad(
                                 </button>
                             }
                         })
-                        .collect::<Vec<_>>()
+                        .collect::<Vec<_>>();
+                    let blank_cells = (0..blanks)
+                        .map(|_| view! { <div class="pad-blank"></div> })
+                        .collect::<Vec<_>>();
+                    (cells, blank_cells)
                 }}
             </div>
             <div class="number-pad-actions">
-                <button class="pad-action-btn" on:click=on_shuffle>
+                <button class="pad-action-btn" on:click=move |_| on_shuffle()>
                     "隨機位置"
                 </button>
-                <button class="pad-action-btn" on:click=on_spin>
+                <button class="pad-action-btn" on:click=move |_| on_spin()>
                     "隨機轉動"
                 </button>
+                <button
+                    class="pad-action-btn"
+                    class:active=move || auto_running.get()
+                    on:click=on_auto
+                >
+                    {move || if auto_running.get() { "停止" } else { "自動抽選" }}
+                </button>
+            </div>
+
+            <div class="number-pad-auto-delay">
+                <label>{move || format!("間隔 {:.1} 秒", auto_delay.get() as f64 / 1000.0)}</label>
+                <input
+                    type="range"
+                    min="300"
+                    max="3000"
+                    step="100"
+                    prop:value=move || auto_delay.get().to_string()
+                    prop:disabled=move || auto_running.get()
+                    on:input=move |ev| {
+                        let val = event_target_value(&ev).parse::<u32>().unwrap_or(800);
+                        set_auto_delay.set(val);
+                    }
+                />
+            </div>
+
+            <div class="number-pad-layout">
+                {move || {
+                    let len = grid_numbers.with(Vec::len);
+                    PadLayout::presets(len)
+                        .into_iter()
+                        .map(|preset| {
+                            let auto = preset == PadLayout::auto(len);
+                            view! {
+                                <button
+                                    class="option-btn"
+                                    class:active=move || layout.get() == preset
+                                    on:click=move |_| {
+                                        animator.cancel();
+                                        set_layout.set((!auto).then_some(preset));
+                                    }
+                                >
+                                    {preset.label()}
+                                </button>
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                }}
             </div>
 
             <div class="number-pad-size">
@@ -0,0 +1,743 @@
+//! 九宮格的亂數操作：與 Leptos 元件無關，畫面與驗證共用同一套步驟，
+//! 相同種子與操作順序一定得到相同的盤面
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::engine::{DigitWeights, GameState};
+
+/// transform 配對數量；九宮格的 transform 以此範圍內的索引表示
+pub const TRANSFORM_COUNT: usize = 8;
+
+/// 九宮格的列數與欄數；格子由左而右、由上而下排列，格子數不足的位置留空
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PadLayout {
+    pub rows: usize,
+    pub cols: usize,
+}
+
+impl PadLayout {
+    /// 預設欄數
+    const DEFAULT_COLS: usize = 3;
+    /// 列數與欄數的上限
+    const MAX_SIDE: usize = 10;
+
+    /// 預設排法：3 欄，列數依格子數
+    pub fn auto(len: usize) -> Self {
+        Self {
+            rows: len.div_ceil(Self::DEFAULT_COLS).max(1),
+            cols: Self::DEFAULT_COLS,
+        }
+    }
+
+    /// 可以放下 `len` 格
+    pub fn fits(self, len: usize) -> bool {
+        (1..=Self::MAX_SIDE).contains(&self.rows)
+            && (1..=Self::MAX_SIDE).contains(&self.cols)
+            && self.rows * self.cols >= len
+    }
+
+    /// 由 URL 參數解析，例如 "2x5"（2 列 5 欄）
+    pub fn parse(s: &str) -> Option<Self> {
+        let (rows, cols) = s.split_once('x')?;
+        let layout = Self {
+            rows: rows.parse().ok()?,
+            cols: cols.parse().ok()?,
+        };
+        layout.fits(0).then_some(layout)
+    }
+
+    pub fn to_query(self) -> String {
+        format!("{}x{}", self.rows, self.cols)
+    }
+
+    pub fn label(self) -> String {
+        format!("{} × {}", self.rows, self.cols)
+    }
+
+    /// 供選擇的排法：預設排法、沒有整列或整欄空白的排法，以及最小的正方形
+    pub fn presets(len: usize) -> Vec<Self> {
+        let mut presets = vec![Self::auto(len)];
+        let square = (1..=Self::MAX_SIDE).find(|n| n * n >= len);
+        let candidates = (1..=Self::MAX_SIDE)
+            .map(|rows| Self {
+                rows,
+                cols: len.div_ceil(rows).max(1),
+            })
+            .filter(|l| (l.rows - 1) * l.cols < len && l.rows * (l.cols - 1) < len)
+            .chain(square.map(|n| Self { rows: n, cols: n }));
+        for layout in candidates {
+            if layout.fits(len) && !presets.contains(&layout) {
+                presets.push(layout);
+            }
+        }
+        presets
+    }
+
+    /// 第 `row` 列的格子索引
+    fn row_cells(self, len: usize, row: usize) -> Vec<usize> {
+        let start = row * self.cols;
+        (start..(start + self.cols).min(len)).collect()
+    }
+
+    /// 第 `col` 欄的格子索引
+    fn col_cells(self, len: usize, col: usize) -> Vec<usize> {
+        (col..len).step_by(self.cols).collect()
+    }
+
+    /// 有格子的各列
+    fn lines_by_row(self, len: usize) -> Vec<Vec<usize>> {
+        (0..len.div_ceil(self.cols))
+            .map(|r| self.row_cells(len, r))
+            .collect()
+    }
+
+    /// 有格子的各欄
+    fn lines_by_col(self, len: usize) -> Vec<Vec<usize>> {
+        (0..self.cols.min(len))
+            .map(|c| self.col_cells(len, c))
+            .collect()
+    }
+
+    /// 左上角完整正方形區塊的邊長
+    fn square_size(self, len: usize) -> usize {
+        self.cols.min(len / self.cols)
+    }
+}
+
+/// 轉動方向
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RotateDir {
+    /// 所有 row 向右循環
+    RowRight,
+    /// 所有 column 向下循環
+    ColDown,
+}
+
+/// 隨機轉動的方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpinStyle {
+    /// 換 transform 或所有 row/column 循環位移
+    #[default]
+    Classic,
+    Transpose,
+    AntiTranspose,
+    /// 單一 row/column 循環位移
+    ShiftLine,
+    /// 交換兩個 row 或兩個 column
+    SwapLines,
+    /// 外圈循環位移
+    Ring,
+    /// 以上所有排列變換隨機挑一種
+    Mixed,
+}
+
+impl SpinStyle {
+    pub const ALL: [SpinStyle; 7] = [
+        SpinStyle::Classic,
+        SpinStyle::Transpose,
+        SpinStyle::AntiTranspose,
+        SpinStyle::ShiftLine,
+        SpinStyle::SwapLines,
+        SpinStyle::Ring,
+        SpinStyle::Mixed,
+    ];
+
+    /// 由 URL 參數解析
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|style| style.to_query() == s)
+    }
+
+    pub fn to_query(self) -> &'static str {
+        match self {
+            Self::Classic => "classic",
+            Self::Transpose => "transpose",
+            Self::AntiTranspose => "anti",
+            Self::ShiftLine => "shift",
+            Self::SwapLines => "swap",
+            Self::Ring => "ring",
+            Self::Mixed => "mixed",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Classic => "翻轉",
+            Self::Transpose => "轉置",
+            Self::AntiTranspose => "反轉置",
+            Self::ShiftLine => "單排位移",
+            Self::SwapLines => "交換兩排",
+            Self::Ring => "外圈旋轉",
+            Self::Mixed => "混合",
+        }
+    }
+}
+
+/// 九宮格的排列變換；row/column 從 0 開始，最後一列可以不滿
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Permutation {
+    /// 沿左上到右下的對角線翻轉（左上角的正方形區塊）
+    Transpose,
+    /// 沿右上到左下的對角線翻轉（左上角的正方形區塊）
+    AntiTranspose,
+    /// 一個 row 向右（`forward`）或向左循環位移一格
+    ShiftRow {
+        row: usize,
+        forward: bool,
+    },
+    /// 一個 column 向下（`forward`）或向上循環位移一格
+    ShiftCol {
+        col: usize,
+        forward: bool,
+    },
+    SwapRows(usize, usize),
+    SwapCols(usize, usize),
+    /// 左上角正方形區塊的外圈順時針（`clockwise`）或逆時針循環位移一格
+    Ring {
+        clockwise: bool,
+    },
+}
+
+/// 隨機轉動的結果
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Spin {
+    /// 整個九宮格換成另一個 transform
+    Transform(usize),
+    /// 所有 row/column 循環位移一格
+    Rotate(RotateDir),
+    /// 排列變換
+    Permute(Permutation),
+}
+
+/// 隨機選取一個與 `current` 不同的 transform 配對索引
+fn random_transform(current: usize, rng: &mut impl Rng) -> usize {
+    let candidates: Vec<usize> = (0..TRANSFORM_COUNT).filter(|&i| i != current).collect();
+    *candidates.choose(rng).unwrap()
+}
+
+/// 隨機選擇方向
+fn random_rotate_dir(rng: &mut impl Rng) -> RotateDir {
+    if rng.gen_bool(0.5) {
+        RotateDir::RowRight
+    } else {
+        RotateDir::ColDown
+    }
+}
+
+/// 隨機轉動：依 `style` 隨機挑一種排列變換；翻轉或沒有可用的變換時，
+/// 一半機率換 transform，一半機率循環位移
+pub fn spin(
+    current: usize,
+    style: SpinStyle,
+    layout: PadLayout,
+    len: usize,
+    rng: &mut impl Rng,
+) -> Spin {
+    if let Some(&perm) = permutations(style, layout, len).choose(rng) {
+        return Spin::Permute(perm);
+    }
+    if rng.gen_bool(0.5) {
+        Spin::Transform(random_transform(current, rng))
+    } else {
+        Spin::Rotate(random_rotate_dir(rng))
+    }
+}
+
+/// 長度相同、可以互換的兩排
+fn same_length_pairs(lines: &[Vec<usize>]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for a in 0..lines.len() {
+        for b in a + 1..lines.len() {
+            if lines[a].len() == lines[b].len() {
+                pairs.push((a, b));
+            }
+        }
+    }
+    pairs
+}
+
+/// `style` 在 `len` 格的九宮格上可用的排列變換；翻轉不使用排列變換
+pub fn permutations(style: SpinStyle, layout: PadLayout, len: usize) -> Vec<Permutation> {
+    let square = layout.square_size(len);
+    let rows = layout.lines_by_row(len);
+    let cols = layout.lines_by_col(len);
+    match style {
+        SpinStyle::Classic => Vec::new(),
+        SpinStyle::Transpose if square >= 2 => vec![Permutation::Transpose],
+        SpinStyle::AntiTranspose if square >= 2 => vec![Permutation::AntiTranspose],
+        SpinStyle::Transpose | SpinStyle::AntiTranspose => Vec::new(),
+        SpinStyle::ShiftLine => {
+            let shift_rows = (0..rows.len())
+                .filter(|&r| rows[r].len() >= 2)
+                .flat_map(|row| {
+                    [true, false].map(|forward| Permutation::ShiftRow { row, forward })
+                });
+            let shift_cols = (0..cols.len())
+                .filter(|&c| cols[c].len() >= 2)
+                .flat_map(|col| {
+                    [true, false].map(|forward| Permutation::ShiftCol { col, forward })
+                });
+            shift_rows.chain(shift_cols).collect()
+        }
+        SpinStyle::SwapLines => same_length_pairs(&rows)
+            .into_iter()
+            .map(|(a, b)| Permutation::SwapRows(a, b))
+            .chain(
+                same_length_pairs(&cols)
+                    .into_iter()
+                    .map(|(a, b)| Permutation::SwapCols(a, b)),
+            )
+            .collect(),
+        SpinStyle::Ring if square >= 3 => [true, false]
+            .map(|clockwise| Permutation::Ring { clockwise })
+            .to_vec(),
+        SpinStyle::Ring => Vec::new(),
+        SpinStyle::Mixed => [
+            SpinStyle::Transpose,
+            SpinStyle::AntiTranspose,
+            SpinStyle::ShiftLine,
+            SpinStyle::SwapLines,
+            SpinStyle::Ring,
+        ]
+        .into_iter()
+        .flat_map(|style| permutations(style, layout, len))
+        .collect(),
+    }
+}
+
+/// 排列變換拆成數個循環：每個循環中第 k 格的數字移到第 k+1 格，最後一格移到第一格
+fn cycles(perm: Permutation, layout: PadLayout, len: usize) -> Vec<Vec<usize>> {
+    let square = layout.square_size(len);
+    let at = |row: usize, col: usize| row * layout.cols + col;
+    let reversed = |mut cells: Vec<usize>, forward: bool| {
+        if !forward {
+            cells.reverse();
+        }
+        cells
+    };
+    match perm {
+        Permutation::Transpose => (0..square)
+            .flat_map(|r| (r + 1..square).map(move |c| vec![at(r, c), at(c, r)]))
+            .collect(),
+        Permutation::AntiTranspose => (0..square)
+            .flat_map(|r| {
+                (0..square - 1 - r).map(move |c| vec![at(r, c), at(square - 1 - c, square - 1 - r)])
+            })
+            .collect(),
+        Permutation::ShiftRow { row, forward } => {
+            vec![reversed(layout.row_cells(len, row), forward)]
+        }
+        Permutation::ShiftCol { col, forward } => {
+            vec![reversed(layout.col_cells(len, col), forward)]
+        }
+        Permutation::SwapRows(a, b) => layout
+            .row_cells(len, a)
+            .into_iter()
+            .zip(layout.row_cells(len, b))
+            .map(|(x, y)| vec![x, y])
+            .collect(),
+        Permutation::SwapCols(a, b) => layout
+            .col_cells(len, a)
+            .into_iter()
+            .zip(layout.col_cells(len, b))
+            .map(|(x, y)| vec![x, y])
+            .collect(),
+        Permutation::Ring { clockwise } => {
+            let last = square - 1;
+            let ring = (0..last)
+                .map(|c| at(0, c))
+                .chain((0..last).map(|r| at(r, last)))
+                .chain((1..=last).rev().map(|c| at(last, c)))
+                .chain((1..=last).rev().map(|r| at(r, 0)))
+                .collect();
+            vec![reversed(ring, clockwise)]
+        }
+    }
+}
+
+/// 每一格的數字移到哪一格
+fn destinations(perm: Permutation, layout: PadLayout, len: usize) -> Vec<usize> {
+    let mut dest: Vec<usize> = (0..len).collect();
+    for cycle in cycles(perm, layout, len) {
+        for (k, &i) in cycle.iter().enumerate() {
+            dest[i] = cycle[(k + 1) % cycle.len()];
+        }
+    }
+    dest
+}
+
+/// 套用排列變換
+pub fn permute(nums: &mut [u8], layout: PadLayout, perm: Permutation) {
+    let original = nums.to_vec();
+    for (i, d) in destinations(perm, layout, nums.len())
+        .into_iter()
+        .enumerate()
+    {
+        nums[d] = original[i];
+    }
+}
+
+/// 排列變換時每一格移動的 (欄數, 列數)，動畫用
+pub fn permutation_offsets(perm: Permutation, layout: PadLayout, len: usize) -> Vec<(i32, i32)> {
+    let cols = layout.cols as i32;
+    destinations(perm, layout, len)
+        .into_iter()
+        .enumerate()
+        .map(|(i, d)| {
+            let (i, d) = (i as i32, d as i32);
+            (d % cols - i % cols, d / cols - i / cols)
+        })
+        .collect()
+}
+
+/// 第 `transform` 種 transform 將格子座標 (x, y)（以九宮格中心為原點、y 向下）
+/// 映射到畫面上的座標；順序與 `number_pad` 的 `TRANSFORM_PAIRS` 相同
+fn apply_transform(transform: usize, x: i32, y: i32) -> (i32, i32) {
+    match transform {
+        1 => (-y, x),
+        2 => (-x, -y),
+        3 => (y, -x),
+        4 => (-x, y),
+        5 => (x, -y),
+        6 => (-y, -x),
+        7 => (y, x),
+        _ => (x, y),
+    }
+}
+
+/// 套用 transform 後畫面上的 (列數, 欄數)
+pub fn screen_size(layout: PadLayout, transform: usize) -> (usize, usize) {
+    match transform {
+        1 | 3 | 6 | 7 => (layout.cols, layout.rows),
+        _ => (layout.rows, layout.cols),
+    }
+}
+
+/// 第 `index` 格在畫面上的 (列, 欄)，由上而下、由左而右從 0 開始
+pub fn screen_position(index: usize, layout: PadLayout, transform: usize) -> (usize, usize) {
+    let rows = layout.rows as i32;
+    let cols = layout.cols as i32;
+    // 座標乘 2，讓中心落在整數上
+    let x = 2 * (index as i32 % cols) - (cols - 1);
+    let y = 2 * (index as i32 / cols) - (rows - 1);
+    let (sx, sy) = apply_transform(transform, x, y);
+    let (screen_rows, screen_cols) = screen_size(layout, transform);
+    (
+        ((sy + screen_rows as i32 - 1) / 2) as usize,
+        ((sx + screen_cols as i32 - 1) / 2) as usize,
+    )
+}
+
+/// 畫面上第 `row` 列、第 `col` 欄的格子索引；該位置沒有格子時為 `None`
+pub fn cell_at(
+    len: usize,
+    layout: PadLayout,
+    transform: usize,
+    row: usize,
+    col: usize,
+) -> Option<usize> {
+    (0..len).find(|&i| screen_position(i, layout, transform) == (row, col))
+}
+
+/// 實體數字鍵盤的按鍵在畫面上的 (列, 欄)：7-8-9／4-5-6／1-2-3 由上而下，0 為第四列第一格
+pub fn numpad_position(key: u32) -> Option<(usize, usize)> {
+    match key {
+        0 => Some((3, 0)),
+        1..=9 => Some(((9 - key as usize) / 3, (key as usize - 1) % 3)),
+        _ => None,
+    }
+}
+
+/// 依方向將所有 row/column 做循環位移；最後一列可以不滿
+pub fn rotate_lines(nums: &mut [u8], layout: PadLayout, dir: RotateDir) {
+    let len = nums.len();
+    let lines = match dir {
+        RotateDir::RowRight => layout.lines_by_row(len),
+        RotateDir::ColDown => layout.lines_by_col(len),
+    };
+
+    for indices in &lines {
+        // [a, b, c] -> [c, a, b]
+        let values: Vec<u8> = indices.iter().map(|&i| nums[i]).collect();
+        for (k, &i) in indices.iter().enumerate() {
+            nums[i] = values[(k + values.len() - 1) % values.len()];
+        }
+    }
+}
+
+/// 打亂排列，保證與原本不同；少於兩格時不動
+pub fn shuffle(nums: &mut [u8], rng: &mut impl Rng) {
+    if nums.len() < 2 {
+        return;
+    }
+    let original = nums.to_vec();
+    loop {
+        nums.shuffle(rng);
+        if nums != original {
+            break;
+        }
+    }
+}
+
+/// 看不到數字時，依權重決定被點的格子翻開哪個數字：把抽中的數字換到這一格。
+/// 回傳實際要按下的數字；權重為預設、`num` 不可選或是特殊格時不動，特殊格也不會被換走
+pub fn weighted_remap(
+    state: &GameState,
+    grid: &mut [u8],
+    num: u8,
+    weights: &DigitWeights,
+    rng: &mut impl Rng,
+) -> u8 {
+    if *weights == DigitWeights::default() || num > 9 || !state.can_pick(num) {
+        return num;
+    }
+    let candidates: Vec<u8> = grid
+        .iter()
+        .copied()
+        .filter(|&d| d <= 9 && state.can_pick(d))
+        .collect();
+    match weights.pick(&candidates, rng).filter(|&d| d != num) {
+        Some(d) => {
+            for cell in grid.iter_mut() {
+                if *cell == num {
+                    *cell = d;
+                } else if *cell == d {
+                    *cell = num;
+                }
+            }
+            d
+        }
+        None => num,
+    }
+}
+
+/// 自動抽選：從目前可選的格子中隨機挑一個
+pub fn auto_choice(state: &GameState, grid: &[u8], rng: &mut impl Rng) -> Option<u8> {
+    let candidates: Vec<u8> = grid
+        .iter()
+        .copied()
+        .filter(|&n| state.can_pick(n))
+        .collect();
+    candidates.choose(rng).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    /// 測試用的排法與格子數
+    const SHAPES: [(usize, usize, usize); 6] = [
+        (3, 3, 9),
+        (4, 3, 10),
+        (2, 5, 10),
+        (4, 4, 10),
+        (5, 2, 10),
+        (1, 10, 10),
+    ];
+
+    fn shapes() -> impl Iterator<Item = (PadLayout, usize)> {
+        SHAPES
+            .into_iter()
+            .map(|(rows, cols, len)| (PadLayout { rows, cols }, len))
+    }
+
+    fn cells(len: usize) -> Vec<u8> {
+        (0..len as u8).collect()
+    }
+
+    #[test]
+    fn permutations_are_bijections() {
+        for (layout, len) in shapes() {
+            for perm in permutations(SpinStyle::Mixed, layout, len) {
+                let mut nums = cells(len);
+                permute(&mut nums, layout, perm);
+                let mut sorted = nums.clone();
+                sorted.sort_unstable();
+                assert_eq!(sorted, cells(len), "{:?} {:?}", layout, perm);
+                assert_ne!(nums, cells(len), "{:?} {:?}", layout, perm);
+                // 位移量與實際移動一致
+                for (i, (dx, dy)) in permutation_offsets(perm, layout, len)
+                    .into_iter()
+                    .enumerate()
+                {
+                    let dest = nums.iter().position(|&n| n as usize == i).unwrap() as i32;
+                    let cols = layout.cols as i32;
+                    assert_eq!(dest, i as i32 + dy * cols + dx);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn permutation_cycles_on_3x3() {
+        let layout = PadLayout::auto(9);
+        let apply = |perm| {
+            let mut nums: Vec<u8> = (1..=9).collect();
+            permute(&mut nums, layout, perm);
+            nums
+        };
+        assert_eq!(apply(Permutation::Transpose), [1, 4, 7, 2, 5, 8, 3, 6, 9]);
+        assert_eq!(
+            apply(Permutation::AntiTranspose),
+            [9, 6, 3, 8, 5, 2, 7, 4, 1]
+        );
+        assert_eq!(
+            apply(Permutation::Ring { clockwise: true }),
+            [4, 1, 2, 7, 5, 3, 8, 9, 6]
+        );
+        assert_eq!(
+            apply(Permutation::ShiftRow {
+                row: 1,
+                forward: true
+            }),
+            [1, 2, 3, 6, 4, 5, 7, 8, 9]
+        );
+        assert_eq!(
+            apply(Permutation::ShiftCol {
+                col: 0,
+                forward: false
+            }),
+            [4, 2, 3, 7, 5, 6, 1, 8, 9]
+        );
+        assert_eq!(
+            apply(Permutation::SwapCols(0, 2)),
+            [3, 2, 1, 6, 5, 4, 9, 8, 7]
+        );
+    }
+
+    #[test]
+    fn shifts_and_rings_invert() {
+        for (layout, len) in shapes() {
+            for perm in permutations(SpinStyle::Mixed, layout, len) {
+                let inverse = match perm {
+                    Permutation::ShiftRow { row, forward } => Permutation::ShiftRow {
+                        row,
+                        forward: !forward,
+                    },
+                    Permutation::ShiftCol { col, forward } => Permutation::ShiftCol {
+                        col,
+                        forward: !forward,
+                    },
+                    Permutation::Ring { clockwise } => Permutation::Ring {
+                        clockwise: !clockwise,
+                    },
+                    // 對角線翻轉與交換是自己的反函數
+                    other => other,
+                };
+                let mut nums = cells(len);
+                permute(&mut nums, layout, perm);
+                permute(&mut nums, layout, inverse);
+                assert_eq!(nums, cells(len), "{:?} {:?}", layout, perm);
+            }
+        }
+    }
+
+    #[test]
+    fn screen_position_of_top_left_cell() {
+        let layout = PadLayout::auto(9);
+        let expected = [
+            (0, 0),
+            (0, 2),
+            (2, 2),
+            (2, 0),
+            (0, 2),
+            (2, 0),
+            (2, 2),
+            (0, 0),
+        ];
+        for (transform, &pos) in expected.iter().enumerate() {
+            assert_eq!(screen_position(0, layout, transform), pos, "{}", transform);
+        }
+    }
+
+    #[test]
+    fn cell_at_inverts_screen_position() {
+        for (layout, len) in shapes() {
+            for transform in 0..TRANSFORM_COUNT {
+                let (rows, cols) = screen_size(layout, transform);
+                assert_eq!(rows * cols, layout.rows * layout.cols);
+                let mut seen = Vec::new();
+                for i in 0..len {
+                    let (r, c) = screen_position(i, layout, transform);
+                    assert!(r < rows && c < cols, "{:?} {} {}", layout, transform, i);
+                    assert_eq!(cell_at(len, layout, transform, r, c), Some(i));
+                    seen.push((r, c));
+                }
+                seen.sort_unstable();
+                seen.dedup();
+                assert_eq!(seen.len(), len);
+            }
+        }
+    }
+
+    #[test]
+    fn numpad_keys_follow_the_physical_keypad() {
+        let layout = PadLayout::auto(9);
+        let at = |key| {
+            let (r, c) = numpad_position(key).unwrap();
+            cell_at(9, layout, 0, r, c)
+        };
+        assert_eq!(at(7), Some(0));
+        assert_eq!(at(9), Some(2));
+        assert_eq!(at(1), Some(6));
+        assert_eq!(at(0), None);
+        assert_eq!(numpad_position(10), None);
+    }
+
+    #[test]
+    fn rotate_lines_with_short_last_row() {
+        let layout = PadLayout::auto(10);
+        let mut nums = cells(10);
+        rotate_lines(&mut nums, layout, RotateDir::RowRight);
+        assert_eq!(nums, [2, 0, 1, 5, 3, 4, 8, 6, 7, 9]);
+        let mut nums = cells(10);
+        rotate_lines(&mut nums, layout, RotateDir::ColDown);
+        assert_eq!(nums, [9, 7, 8, 0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn layouts() {
+        assert_eq!(
+            PadLayout::parse("2x5"),
+            Some(PadLayout { rows: 2, cols: 5 })
+        );
+        assert_eq!(PadLayout::parse("0x5"), None);
+        assert_eq!(PadLayout::parse("11x1"), None);
+        assert!(!PadLayout { rows: 2, cols: 4 }.fits(9));
+        let presets = PadLayout::presets(10);
+        assert_eq!(presets[0], PadLayout::auto(10));
+        assert!(presets.iter().all(|l| l.fits(10)));
+        assert!(presets.contains(&PadLayout { rows: 2, cols: 5 }));
+        assert!(presets.contains(&PadLayout { rows: 4, cols: 4 }));
+    }
+
+    #[test]
+    fn shuffle_always_changes_the_grid() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..100 {
+            let mut nums = vec![1, 2];
+            shuffle(&mut nums, &mut rng);
+            assert_eq!(nums, [2, 1]);
+        }
+        let mut single = vec![1];
+        shuffle(&mut single, &mut rng);
+        assert_eq!(single, [1]);
+    }
+
+    #[test]
+    fn classic_spin_keeps_old_rng_sequence() {
+        // 翻轉不消耗排列變換的亂數，舊的驗證紀錄仍能重播
+        let mut a = ChaCha8Rng::seed_from_u64(7);
+        let mut b = ChaCha8Rng::seed_from_u64(7);
+        let spin = spin(0, SpinStyle::Classic, PadLayout::auto(9), 9, &mut a);
+        let expected = if b.gen_bool(0.5) {
+            Spin::Transform(random_transform(0, &mut b))
+        } else {
+            Spin::Rotate(random_rotate_dir(&mut b))
+        };
+        assert_eq!(spin, expected);
+    }
+}
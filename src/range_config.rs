@@ -1,9 +1,11 @@
 use leptos::prelude::*;
 
 #[derive(Clone, Copy, PartialEq)]
+#[allow(clippy::enum_variant_names)]
 pub enum LimitMode {
     ByDigits,
     ByMaxAmount,
+    ByRange,
 }
 
 #[component]
@@ -14,6 +16,10 @@ pub fn RangeConfig(
     set_selected_digits: WriteSignal<u8>,
     max_amount: ReadSignal<u64>,
     set_max_amount: WriteSignal<u64>,
+    min_amount: ReadSignal<u64>,
+    set_min_amount: WriteSignal<u64>,
+    /// 目前範圍無法組出任何金額
+    infeasible: Signal<bool>,
 ) -> impl IntoView {
     view! {
         <div class="range-config">
@@ -32,9 +38,29 @@ pub fn RangeConfig(
                 >
                     "選擇最大金額"
                 </button>
+                <button
+                    class:active=move || mode.get() == LimitMode::ByRange
+                    on:click=move |_| set_mode.set(LimitMode::ByRange)
+                >
+                    "選擇金額範圍"
+                </button>
             </div>
 
             {move || {
+                let max_input = move || {
+                    view! {
+                        <input
+                            type="number"
+                            min="1"
+                            max="987654321"
+                            prop:value=move || max_amount.get().to_string()
+                            on:input=move |ev| {
+                                let val = event_target_value(&ev).parse::<u64>().unwrap_or(0);
+                                set_max_amount.set(val);
+                            }
+                        />
+                    }
+                };
                 if mode.get() == LimitMode::ByDigits {
                     {
                         let on_dec = move |_| {
@@ -58,25 +84,40 @@ pub fn RangeConfig(
                         }
                     }
                         .into_any()
-                } else {
+                } else if mode.get() == LimitMode::ByMaxAmount {
                     view! {
                         <div class="max-amount-input">
                             <label>"最大金額: "</label>
+                            {max_input()}
+                        </div>
+                    }
+                        .into_any()
+                } else {
+                    view! {
+                        <div class="max-amount-input">
+                            <label>"最小金額: "</label>
                             <input
                                 type="number"
-                                min="1"
+                                min="0"
                                 max="987654321"
-                                prop:value=move || max_amount.get().to_string()
+                                prop:value=move || min_amount.get().to_string()
                                 on:input=move |ev| {
                                     let val = event_target_value(&ev).parse::<u64>().unwrap_or(0);
-                                    set_max_amount.set(val);
+                                    set_min_amount.set(val);
                                 }
                             />
                         </div>
+                        <div class="max-amount-input">
+                            <label>"最大金額: "</label>
+                            {max_input()}
+                        </div>
                     }
                         .into_any()
                 }
             }}
+            <Show when=move || infeasible.get()>
+                <p class="range-warning">"此範圍無法組出任何金額"</p>
+            </Show>
         </div>
     }
 }
@@ -0,0 +1,239 @@
+use leptos::prelude::*;
+
+/// 輸入金額的上限，與最大金額相同
+const MAX_TOTAL: u64 = 987_654_321;
+
+/// 紅包分配：總額分給固定人數，每人依序抽一份
+#[derive(Clone, PartialEq)]
+pub struct RedEnvelope {
+    pub total: u64,
+    pub people: usize,
+    /// 每人至少拿到的金額
+    pub min_each: u64,
+    /// 目前是第幾份
+    turn: usize,
+    /// 各份抽出的金額，索引為份數
+    results: Vec<u64>,
+}
+
+impl RedEnvelope {
+    /// 總額不足以讓每人拿到最少金額時為 `None`
+    pub fn new(total: u64, people: usize, min_each: u64) -> Option<Self> {
+        let min_each = min_each.max(1);
+        let enough = min_each
+            .checked_mul(people as u64)
+            .is_some_and(|least| total >= least);
+        (people > 0 && total <= MAX_TOTAL && enough).then(|| Self {
+            total,
+            people,
+            min_each,
+            turn: 0,
+            results: Vec::new(),
+        })
+    }
+
+    /// 所有份數都已有結果
+    pub fn is_over(&self) -> bool {
+        self.turn >= self.people
+    }
+
+    /// 目前是第幾份（從 1 開始）
+    pub fn current_turn(&self) -> usize {
+        self.turn + 1
+    }
+
+    pub fn results(&self) -> &[u64] {
+        &self.results
+    }
+
+    /// 還沒分出去的金額（含目前這份）
+    pub fn remaining(&self) -> u64 {
+        self.total - self.results.iter().sum::<u64>()
+    }
+
+    /// 目前這份可抽的金額範圍：之後每人至少還能拿到最少金額；最後一份為剩下的全部
+    pub fn bounds(&self) -> Option<(u64, u64)> {
+        if self.is_over() {
+            return None;
+        }
+        let pool = self.total - self.results.iter().take(self.turn).sum::<u64>();
+        let after = (self.people - self.turn - 1) as u64;
+        if after == 0 {
+            Some((pool, pool))
+        } else {
+            Some((self.min_each, pool - after * self.min_each))
+        }
+    }
+
+    /// 範圍只剩一個金額時不用抽：最後一份為剩下的金額，
+    /// 總額剛好夠每人拿最少金額時每份都是最少金額
+    pub fn forced_amount(&self) -> Option<u64> {
+        self.bounds().filter(|(lo, hi)| lo == hi).map(|(lo, _)| lo)
+    }
+
+    /// 目前是最後一份
+    pub fn is_last(&self) -> bool {
+        self.turn + 1 == self.people
+    }
+
+    /// 記錄目前這份的結果；同一份重複記錄時覆蓋
+    pub fn record(&mut self, amount: u64) {
+        if self.is_over() {
+            return;
+        }
+        if self.results.len() > self.turn {
+            self.results[self.turn] = amount;
+        } else {
+            self.results.push(amount);
+        }
+    }
+
+    /// 目前這份已有結果時換下一份
+    pub fn advance(&mut self) {
+        if self.results.len() > self.turn {
+            self.turn += 1;
+        }
+    }
+}
+
+#[component]
+pub fn RedEnvelopePanel(
+    envelope: ReadSignal<Option<RedEnvelope>>,
+    set_envelope: WriteSignal<Option<RedEnvelope>>,
+) -> impl IntoView {
+    let (total, set_total) = signal(10_000u64);
+    let (people, set_people) = signal(10usize);
+    let (min_each, set_min_each) = signal(1u64);
+    let (invalid, set_invalid) = signal(false);
+
+    let on_start = move |_| {
+        let split = RedEnvelope::new(total.get(), people.get(), min_each.get());
+        set_invalid.set(split.is_none());
+        if split.is_some() {
+            set_envelope.set(split);
+        }
+    };
+
+    view! {
+        <div class="envelope-panel">
+            <h2>"紅包分配"</h2>
+            {move || match envelope.get() {
+                None => {
+                    view! {
+                        <div class="envelope-setup">
+                            <div class="envelope-field">
+                                <label>"總金額: "</label>
+                                <input
+                                    type="number"
+                                    min="1"
+                                    prop:value=move || total.get().to_string()
+                                    on:input=move |ev| {
+                                        let val = event_target_value(&ev).parse::<u64>().unwrap_or(0);
+                                        set_total.set(val);
+                                    }
+                                />
+                            </div>
+                            <div class="envelope-field">
+                                <label>"人數: "</label>
+                                <input
+                                    type="number"
+                                    min="1"
+                                    prop:value=move || people.get().to_string()
+                                    on:input=move |ev| {
+                                        let val = event_target_value(&ev).parse::<u64>().unwrap_or(0);
+                                        set_people.set(val.min(99) as usize);
+                                    }
+                                />
+                            </div>
+                            <div class="envelope-field">
+                                <label>"每人至少: "</label>
+                                <input
+                                    type="number"
+                                    min="1"
+                                    prop:value=move || min_each.get().to_string()
+                                    on:input=move |ev| {
+                                        let val = event_target_value(&ev).parse::<u64>().unwrap_or(0);
+                                        set_min_each.set(val);
+                                    }
+                                />
+                            </div>
+                            <Show when=move || invalid.get()>
+                                <p class="range-warning">"總金額不足以讓每人拿到最少金額"</p>
+                            </Show>
+                            <button class="option-btn" on:click=on_start>
+                                "開始分配"
+                            </button>
+                        </div>
+                    }
+                        .into_any()
+                }
+                Some(e) => {
+                    let status = match (e.bounds(), e.forced_amount()) {
+                        (_, Some(amount)) if e.is_last() => {
+                            format!("第 {} / {} 份（最後一份）：{}", e.current_turn(), e.people, amount)
+                        }
+                        (_, Some(amount)) => {
+                            format!("第 {} / {} 份（固定金額）：{}", e.current_turn(), e.people, amount)
+                        }
+                        (Some((lo, hi)), None) => {
+                            format!("第 {} / {} 份：{} - {}", e.current_turn(), e.people, lo, hi)
+                        }
+                        (None, None) => "分配完成".to_string(),
+                    };
+                    view! {
+                        <div class="envelope-board">
+                            <h3>{status}</h3>
+                            <ol class="envelope-results">
+                                {e
+                                    .results()
+                                    .iter()
+                                    .map(|amount| view! { <li>{amount.to_string()}</li> })
+                                    .collect::<Vec<_>>()}
+                            </ol>
+                            <p class="envelope-remaining">
+                                {format!("剩餘：{} / {}", e.remaining(), e.total)}
+                            </p>
+                            <button class="option-btn" on:click=move |_| set_envelope.set(None)>
+                                "結束分配"
+                            </button>
+                        </div>
+                    }
+                        .into_any()
+                }
+            }}
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_share_is_forced() {
+        let mut e = RedEnvelope::new(100, 2, 10).unwrap();
+        assert_eq!(e.bounds(), Some((10, 90)));
+        assert_eq!(e.forced_amount(), None);
+        e.record(30);
+        e.advance();
+        assert!(e.is_last());
+        assert_eq!(e.forced_amount(), Some(70));
+        e.record(70);
+        e.advance();
+        assert!(e.is_over());
+        assert_eq!(e.forced_amount(), None);
+    }
+
+    #[test]
+    fn exact_total_forces_every_share() {
+        let mut e = RedEnvelope::new(100, 10, 10).unwrap();
+        for _ in 0..10 {
+            assert_eq!(e.bounds(), Some((10, 10)));
+            assert_eq!(e.forced_amount(), Some(10));
+            e.record(10);
+            e.advance();
+        }
+        assert!(e.is_over());
+        assert!(RedEnvelope::new(99, 10, 10).is_none());
+    }
+}
@@ -0,0 +1,193 @@
+use leptos::prelude::*;
+
+/// 多人輪流抽選的場次
+#[derive(Clone, PartialEq)]
+pub struct Session {
+    pub players: Vec<String>,
+    /// 每人抽幾次
+    pub rounds: u8,
+    /// 目前輪次（依玩家順序輪流）
+    turn: usize,
+    /// 各輪次的結果，索引為輪次
+    results: Vec<u64>,
+}
+
+/// 排行榜上的一列
+pub struct Standing {
+    pub name: String,
+    pub total: u64,
+    /// 已抽次數
+    pub played: usize,
+}
+
+impl Session {
+    pub fn new(players: Vec<String>, rounds: u8) -> Self {
+        Self {
+            players,
+            rounds: rounds.max(1),
+            turn: 0,
+            results: Vec::new(),
+        }
+    }
+
+    fn total_turns(&self) -> usize {
+        self.players.len() * self.rounds as usize
+    }
+
+    /// 所有輪次都已有結果
+    pub fn is_over(&self) -> bool {
+        self.results.len() >= self.total_turns()
+    }
+
+    /// 目前輪到的玩家；場次結束時為 `None`
+    pub fn current_player(&self) -> Option<&str> {
+        if self.is_over() {
+            return None;
+        }
+        self.players
+            .get(self.turn % self.players.len())
+            .map(String::as_str)
+    }
+
+    /// 第幾輪（從 1 開始）
+    pub fn current_round(&self) -> usize {
+        self.turn / self.players.len().max(1) + 1
+    }
+
+    /// 記錄目前輪次的結果；同一輪重複記錄時覆蓋
+    pub fn record(&mut self, amount: u64) {
+        if self.turn >= self.total_turns() {
+            return;
+        }
+        if self.results.len() > self.turn {
+            self.results[self.turn] = amount;
+        } else {
+            self.results.push(amount);
+        }
+    }
+
+    /// 目前輪次已有結果時換下一位
+    pub fn advance(&mut self) {
+        if self.results.len() > self.turn {
+            self.turn += 1;
+        }
+    }
+
+    /// 依總金額由大到小排序的排行榜
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self
+            .players
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let amounts: Vec<u64> = self
+                    .results
+                    .iter()
+                    .skip(i)
+                    .step_by(self.players.len())
+                    .copied()
+                    .collect();
+                Standing {
+                    name: name.clone(),
+                    total: amounts.iter().sum(),
+                    played: amounts.len(),
+                }
+            })
+            .collect();
+        standings.sort_by_key(|s| std::cmp::Reverse(s.total));
+        standings
+    }
+}
+
+#[component]
+pub fn SessionPanel(
+    session: ReadSignal<Option<Session>>,
+    set_session: WriteSignal<Option<Session>>,
+) -> impl IntoView {
+    let (names, set_names) = signal(String::new());
+    let (rounds, set_rounds) = signal(1u8);
+
+    let on_start = move |_| {
+        let players: Vec<String> = names
+            .get()
+            .lines()
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(String::from)
+            .collect();
+        if players.is_empty() {
+            return;
+        }
+        set_session.set(Some(Session::new(players, rounds.get())));
+    };
+
+    view! {
+        <div class="session-panel">
+            <h2>"多人場次"</h2>
+            {move || match session.get() {
+                None => {
+                    view! {
+                        <div class="session-setup">
+                            <textarea
+                                class="session-names"
+                                placeholder="每行一位參加者"
+                                prop:value=move || names.get()
+                                on:input=move |ev| set_names.set(event_target_value(&ev))
+                            ></textarea>
+                            <div class="session-rounds">
+                                <label>"每人次數: "</label>
+                                <input
+                                    type="number"
+                                    min="1"
+                                    max="99"
+                                    prop:value=move || rounds.get().to_string()
+                                    on:input=move |ev| {
+                                        let val = event_target_value(&ev).parse::<u8>().unwrap_or(1);
+                                        set_rounds.set(val.max(1));
+                                    }
+                                />
+                            </div>
+                            <button class="option-btn" on:click=on_start>
+                                "開始場次"
+                            </button>
+                        </div>
+                    }
+                        .into_any()
+                }
+                Some(s) => {
+                    let title = if s.is_over() {
+                        "最終排行".to_string()
+                    } else {
+                        format!("第 {} / {} 輪", s.current_round(), s.rounds)
+                    };
+                    view! {
+                        <div class="session-board">
+                            <h3>{title}</h3>
+                            <ol class="session-standings">
+                                {s
+                                    .standings()
+                                    .into_iter()
+                                    .map(|st| {
+                                        view! {
+                                            <li class="session-standing">
+                                                <span class="session-name">{st.name}</span>
+                                                <span class="session-played">
+                                                    {format!("{} 次", st.played)}
+                                                </span>
+                                                <span class="session-total">{st.total.to_string()}</span>
+                                            </li>
+                                        }
+                                    })
+                                    .collect::<Vec<_>>()}
+                            </ol>
+                            <button class="option-btn" on:click=move |_| set_session.set(None)>
+                                "結束場次"
+                            </button>
+                        </div>
+                    }
+                        .into_any()
+                }
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+
+use leptos::prelude::*;
+
+use crate::engine::GameState;
+
+/// 選取前的盤面，復原時整個還原
+#[derive(Clone, PartialEq)]
+pub struct Snapshot {
+    pub game: GameState,
+    /// 九宮格排列
+    pub grid: Vec<u8>,
+    /// 九宮格 transform 索引
+    pub transform: usize,
+    /// 本局各位數選取當下的九宮格排列
+    pub pick_layouts: BTreeMap<usize, Vec<u8>>,
+}
+
+/// 本局的復原／重做紀錄
+#[derive(Clone, Default, PartialEq)]
+pub struct UndoHistory {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+}
+
+impl UndoHistory {
+    /// 記錄一次選取前的盤面；新的選取會清掉可重做的步驟
+    pub fn record(&mut self, before: Snapshot) {
+        self.undo.push(before);
+        self.redo.clear();
+    }
+
+    /// 回到上一步；`current` 移到重做堆疊
+    pub fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let prev = self.undo.pop()?;
+        self.redo.push(current);
+        Some(prev)
+    }
+
+    /// 重做上一次復原的步驟；`current` 移回復原堆疊
+    pub fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+#[component]
+pub fn UndoButtons(
+    history: ReadSignal<UndoHistory>,
+    on_undo: Callback<()>,
+    on_redo: Callback<()>,
+) -> impl IntoView {
+    view! {
+        <div class="undo-actions">
+            <button
+                class="option-btn"
+                title="Ctrl+Z"
+                disabled=move || history.with(|h| !h.can_undo())
+                on:click=move |_| on_undo.run(())
+            >
+                "復原"
+            </button>
+            <button
+                class="option-btn"
+                title="Ctrl+Y"
+                disabled=move || history.with(|h| !h.can_redo())
+                on:click=move |_| on_redo.run(())
+            >
+                "重做"
+            </button>
+        </div>
+    }
+}
@@ -0,0 +1,70 @@
+//! 命令列版的腳本化抽選：相同種子與設定一定得到相同結果
+
+use std::process::Command;
+
+use digit_draw::engine::HiddenPolicy;
+use serde_json::Value;
+
+fn draw(args: &[&str]) -> Value {
+    let output = Command::new(env!("CARGO_BIN_EXE_digit-draw-cli"))
+        .args(["--auto", "--json"])
+        .args(args)
+        .output()
+        .expect("無法執行 digit-draw-cli");
+    assert!(output.status.success(), "{:?}", output);
+    serde_json::from_slice(&output.stdout).expect("輸出不是 JSON")
+}
+
+#[test]
+fn seeded_auto_draw_is_reproducible() {
+    let args = ["--max", "150", "--no-repeat", "--seed", "42"];
+    let result = draw(&args);
+    assert_eq!(result, draw(&args));
+    assert_eq!(result["seed"], 42);
+    let amount = result["amount"].as_u64().unwrap();
+    assert!((1..=150).contains(&amount));
+    let digits: Vec<u64> = result["digits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d.as_u64().unwrap())
+        .collect();
+    assert_eq!(digits.len(), 3);
+    let from_digits = digits.iter().rev().fold(0, |acc, d| acc * 10 + d);
+    assert_eq!(from_digits, amount);
+}
+
+#[test]
+fn hidden_positions_follow_the_seed() {
+    let args = ["--digits", "5", "--hidden", "random:2", "--seed", "7"];
+    let result = draw(&args);
+    assert_eq!(result["hidden"], draw(&args)["hidden"]);
+    assert_eq!(result["hidden"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn hidden_positions_match_the_web_app() {
+    // 網頁版以 `HiddenPolicy::rng` 的串流決定第一局的隱藏位數
+    let policy = HiddenPolicy::parse("random:3").unwrap();
+    for seed in 0..10 {
+        let seed_arg = seed.to_string();
+        let result = draw(&["--digits", "6", "--hidden", "random:3", "--seed", &seed_arg]);
+        let expected = policy.hidden_slots(6, &mut HiddenPolicy::rng(seed));
+        let hidden: Vec<usize> = result["hidden"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p.as_u64().unwrap() as usize)
+            .collect();
+        assert_eq!(hidden, expected);
+    }
+}
+
+#[test]
+fn infeasible_range_fails() {
+    let output = Command::new(env!("CARGO_BIN_EXE_digit-draw-cli"))
+        .args(["--auto", "--min", "10", "--max", "10"])
+        .output()
+        .expect("無法執行 digit-draw-cli");
+    assert!(!output.status.success());
+}